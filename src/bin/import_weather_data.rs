@@ -0,0 +1,59 @@
+// Regenerates data/weather_data.json from the community datamined sheets
+//
+// Usage: import_weather_data <sheet directory> [--output <path>]
+//
+// Prints the differences against the bundled weather table, and writes the regenerated table to
+// <path> if given.
+
+use ffxiv_chronowatcher::datamine_import::{diff_weather_tables, import_datamined_sheets};
+use ffxiv_chronowatcher::weather_data::WeatherTable;
+
+use std::env;
+use std::fs;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+    let mut sheet_directory = None;
+    let mut output_path = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" | "-o" => output_path = args.next(),
+            _ if sheet_directory.is_none() => sheet_directory = Some(arg),
+            _ => {
+                eprintln!("Unexpected argument '{}'", arg);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let Some(sheet_directory) = sheet_directory else {
+        eprintln!("Usage: import_weather_data <sheet directory> [--output <path>]");
+        return ExitCode::FAILURE;
+    };
+
+    let import = match import_datamined_sheets(&sheet_directory, WeatherTable::builtin()) {
+        Ok(import) => import,
+        Err(error) => {
+            eprintln!("{}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    for territory in &import.skipped {
+        eprintln!(
+            "Skipped territory {} ({}): {}",
+            territory.territory_id, territory.name, territory.reason
+        );
+    }
+    print!(
+        "{}",
+        diff_weather_tables(WeatherTable::builtin(), &import.table)
+    );
+
+    if let Some(output_path) = output_path {
+        if let Err(error) = fs::write(&output_path, import.table.to_json_string()) {
+            eprintln!("Unable to write '{}': {}", output_path, error);
+            return ExitCode::FAILURE;
+        }
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,173 @@
+use crate::eorzean_time::{ToUnixTimestamp, EARTH_SECONDS_PER_EORZEAN_DAY};
+use crate::eorzean_weather::EARTH_SECONDS_PER_WEATHER_INTERVAL;
+use crate::forecaster::Forecaster;
+
+use std::fmt;
+
+// Earth seconds in an Eorzean minute
+const EARTH_SECONDS_PER_EORZEAN_MINUTE: f64 = 35.0 / 12.0;
+
+/// An observation of the game client's clock, used to calibrate the timing offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationSample {
+    /// The Earth time when the in-game clock turned to an Eorzean time (bells, minutes)
+    EorzeanMinute {
+        earth_time: i64,
+        eorzean_time: (u8, u8),
+    },
+    /// The Earth time when the weather changed in game
+    WeatherChange { earth_time: i64 },
+}
+
+impl CalibrationSample {
+    /// Creates a sample for the in-game clock turning to an Eorzean time
+    pub fn eorzean_minute<T: ToUnixTimestamp>(
+        earth_time: T,
+        eorzean_time: (u8, u8),
+    ) -> CalibrationSample {
+        CalibrationSample::EorzeanMinute {
+            earth_time: earth_time.to_unix_timestamp(),
+            eorzean_time,
+        }
+    }
+
+    /// Creates a sample for the weather changing in game
+    pub fn weather_change<T: ToUnixTimestamp>(earth_time: T) -> CalibrationSample {
+        CalibrationSample::WeatherChange {
+            earth_time: earth_time.to_unix_timestamp(),
+        }
+    }
+
+    // Gets the offset between the observed time and the nearest calculated time of the event,
+    // along with the period the event repeats with
+    fn offset(&self) -> Result<(f64, f64), CalibrationError> {
+        match *self {
+            CalibrationSample::EorzeanMinute {
+                earth_time,
+                eorzean_time: (bells, minutes),
+            } => {
+                if bells >= 24 || minutes >= 60 {
+                    return Err(CalibrationError::InvalidEorzeanTime { bells, minutes });
+                }
+                let period = EARTH_SECONDS_PER_EORZEAN_DAY as f64;
+                let minute_start =
+                    (bells as f64 * 60.0 + minutes as f64) * EARTH_SECONDS_PER_EORZEAN_MINUTE;
+                Ok((
+                    wrap_offset(earth_time as f64 - minute_start, period),
+                    period,
+                ))
+            }
+            CalibrationSample::WeatherChange { earth_time } => {
+                let period = EARTH_SECONDS_PER_WEATHER_INTERVAL as f64;
+                Ok((wrap_offset(earth_time as f64, period), period))
+            }
+        }
+    }
+}
+
+// Wraps an offset into the half-open range (-period / 2, period / 2]
+fn wrap_offset(offset: f64, period: f64) -> f64 {
+    let wrapped = offset.rem_euclid(period);
+    if wrapped > period / 2.0 {
+        wrapped - period
+    } else {
+        wrapped
+    }
+}
+
+/// Errors that can occur while calibrating the timing offset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CalibrationError {
+    /// No samples were given
+    NoSamples,
+    /// A sample has an Eorzean time outside of 00:00 to 23:59
+    InvalidEorzeanTime { bells: u8, minutes: u8 },
+}
+
+impl fmt::Display for CalibrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalibrationError::NoSamples => write!(f, "No calibration samples were given"),
+            CalibrationError::InvalidEorzeanTime { bells, minutes } => {
+                write!(
+                    f,
+                    "Invalid Eorzean time {:02}:{:02} in a calibration sample",
+                    bells, minutes
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CalibrationError {}
+
+/// The timing offset that best fits a set of calibration samples
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calibration {
+    /// The best-fit offset in seconds, rounded to be used as a timing offset
+    pub offset: i64,
+    /// The best-fit offset in seconds before rounding
+    pub exact_offset: f64,
+    /// The standard error of the best-fit offset in seconds, or `None` with a single sample
+    pub standard_error: Option<f64>,
+    /// The largest difference between a sample and the best-fit offset, in seconds
+    pub max_residual: f64,
+    /// The number of samples the offset was fitted to
+    pub sample_count: usize,
+}
+
+/// Calculates the timing offset that best fits observations of the game client
+/// Each sample gives the difference between when the game showed an event and when it is calculated to happen.
+/// The best fit is the mean of these differences, which minimises the squared error. Samples only determine the
+/// offset up to the period of their event, so offsets are taken as the nearest to that of the first sample.
+///
+/// # Arguments
+/// - `samples` - The observations of the game client
+///
+/// # Returns
+/// - The best-fit offset and its error estimate, or an error if there are no samples or a sample is invalid
+pub fn calibrate_timing_offset(
+    samples: &[CalibrationSample],
+) -> Result<Calibration, CalibrationError> {
+    let offsets = samples
+        .iter()
+        .map(|sample| sample.offset())
+        .collect::<Result<Vec<(f64, f64)>, CalibrationError>>()?;
+    let (reference, _) = *offsets.first().ok_or(CalibrationError::NoSamples)?;
+    let offsets: Vec<f64> = offsets
+        .iter()
+        .map(|(offset, period)| reference + wrap_offset(offset - reference, *period))
+        .collect();
+
+    let sample_count = offsets.len();
+    let mean = offsets.iter().sum::<f64>() / sample_count as f64;
+    let standard_error = if sample_count > 1 {
+        let variance = offsets
+            .iter()
+            .map(|offset| (offset - mean).powi(2))
+            .sum::<f64>()
+            / (sample_count - 1) as f64;
+        Some((variance / sample_count as f64).sqrt())
+    } else {
+        None
+    };
+    let max_residual = offsets
+        .iter()
+        .map(|offset| (offset - mean).abs())
+        .fold(0.0, f64::max);
+    Ok(Calibration {
+        offset: mean.round() as i64,
+        exact_offset: mean,
+        standard_error,
+        max_residual,
+        sample_count,
+    })
+}
+
+impl<'a> Forecaster<'a> {
+    /// Sets the timing offset to the result of a calibration
+    /// See `calibrate_timing_offset`
+    pub fn with_calibration(self, calibration: &Calibration) -> Forecaster<'a> {
+        self.with_timing_offset(calibration.offset)
+    }
+}
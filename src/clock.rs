@@ -0,0 +1,209 @@
+use crate::eorzean_time::{
+    convert_to_eorzean_date, convert_to_eorzean_time, eorzean_duration_seconds, EorzeanDate,
+    EorzeanTime, ToUnixTimestamp,
+};
+use crate::eorzean_weather::{EorzeaWeather, SearchHorizon, WeatherChange};
+use crate::forecaster::Forecaster;
+use crate::weather_data::{WeatherDatabase, ZoneKey};
+
+use chrono::{Duration, Utc};
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// A source of the current time
+pub trait Clock {
+    /// Gets the current time
+    ///
+    /// # Returns
+    /// - An `i64` representing the Unix timestamp
+    fn now(&self) -> i64;
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> i64 {
+        (**self).now()
+    }
+}
+
+/// The system time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> i64 {
+        Utc::now().timestamp()
+    }
+}
+
+/// A clock that is stopped at a given time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock {
+    time: i64,
+}
+
+impl FixedClock {
+    /// Creates a clock that always returns `time`
+    pub fn new<T: ToUnixTimestamp>(time: T) -> FixedClock {
+        FixedClock {
+            time: time.to_unix_timestamp(),
+        }
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> i64 {
+        self.time
+    }
+}
+
+/// A clock that runs a fixed number of seconds ahead of (or behind) another clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OffsetClock<C: Clock> {
+    inner: C,
+    offset: i64,
+}
+
+impl<C: Clock> OffsetClock<C> {
+    /// Creates a clock that adds `offset` seconds to the time of `inner`
+    pub fn new(inner: C, offset: i64) -> OffsetClock<C> {
+        OffsetClock { inner, offset }
+    }
+}
+
+impl<C: Clock> Clock for OffsetClock<C> {
+    fn now(&self) -> i64 {
+        self.inner.now() + self.offset
+    }
+}
+
+// Ticks of a `ManualClock` per Earth second. An Eorzean second lasts 35/720 = 7/144 Earth seconds, so a tick is a
+// seventh of an Eorzean second and Eorzean durations move the clock by a whole number of ticks
+const MANUAL_CLOCK_TICKS_PER_SECOND: i64 = 144;
+
+/// A clock that only moves when it is told to, for simulating the passage of time in tests
+/// The time can be changed through a shared reference, so the clock can be passed to code under test by reference.
+/// The clock keeps the fraction of a second left over by Eorzean durations, so many small steps add up exactly.
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    // The time in ticks since the Unix epoch
+    ticks: AtomicI64,
+}
+
+impl ManualClock {
+    /// Creates a clock starting at `time`
+    pub fn new<T: ToUnixTimestamp>(time: T) -> ManualClock {
+        ManualClock {
+            ticks: AtomicI64::new(time.to_unix_timestamp() * MANUAL_CLOCK_TICKS_PER_SECOND),
+        }
+    }
+
+    /// Sets the current time
+    pub fn set<T: ToUnixTimestamp>(&self, time: T) {
+        self.ticks.store(
+            time.to_unix_timestamp() * MANUAL_CLOCK_TICKS_PER_SECOND,
+            Ordering::SeqCst,
+        );
+    }
+
+    /// Moves the clock forward by an Earth duration, or backward if it is negative
+    pub fn advance(&self, duration: Duration) {
+        self.ticks.fetch_add(
+            duration.num_seconds() * MANUAL_CLOCK_TICKS_PER_SECOND,
+            Ordering::SeqCst,
+        );
+    }
+
+    /// Moves the clock forward by an Eorzean duration
+    /// Unlike `eorzea_duration_to_earth_sec`, the part of an Earth second the duration does not fill is kept,
+    /// e.g. sixty steps of one Eorzean minute move the clock by exactly one Eorzean hour
+    pub fn advance_eorzean(&self, eorzean_duration: EorzeanTime) {
+        let ticks = (eorzean_duration_seconds(&eorzean_duration) * 7.0).round() as i64;
+        self.ticks.fetch_add(ticks, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> i64 {
+        self.ticks
+            .load(Ordering::SeqCst)
+            .div_euclid(MANUAL_CLOCK_TICKS_PER_SECOND)
+    }
+}
+
+/// Gets the current Eorzean date
+///
+/// # Arguments
+/// - `clock` - The source of the current time
+///
+/// # Returns
+/// - An EorzeanDate struct, see `convert_to_eorzean_date`
+pub fn current_eorzean_date<C: Clock + ?Sized>(clock: &C) -> EorzeanDate {
+    convert_to_eorzean_date(clock.now())
+}
+
+/// Gets the current Eorzean time of day
+///
+/// # Arguments
+/// - `clock` - The source of the current time
+///
+/// # Returns
+/// - A tuple containing the bells and minutes, see `convert_to_eorzean_time`
+pub fn current_eorzean_time<C: Clock + ?Sized>(clock: &C) -> (u8, u8) {
+    convert_to_eorzean_time(clock.now())
+}
+
+/// Gets the current weather of a zone, using the bundled weather data
+/// See `Forecaster::current_weather`
+pub fn current_weather<Z: ZoneKey, C: Clock + ?Sized>(zone: Z, clock: &C) -> EorzeaWeather {
+    WeatherDatabase::builtin()
+        .forecaster()
+        .current_weather(zone, clock)
+}
+
+/// Finds the next time the weather of a zone changes, using the bundled weather data
+/// See `Forecaster::next_weather_change`
+pub fn next_weather_change<Z: ZoneKey, C: Clock + ?Sized>(
+    zone: Z,
+    clock: &C,
+    horizon: SearchHorizon,
+) -> Option<WeatherChange> {
+    WeatherDatabase::builtin()
+        .forecaster()
+        .next_weather_change(zone, clock, horizon)
+}
+
+impl Forecaster<'_> {
+    /// Gets the current weather of a zone
+    ///
+    /// # Arguments
+    /// - `zone` - The name or `ZoneId` of the zone
+    /// - `clock` - The source of the current time
+    ///
+    /// # Returns
+    /// - An EorzeaWeather struct for the current weather interval
+    pub fn current_weather<Z: ZoneKey, C: Clock + ?Sized>(
+        &self,
+        zone: Z,
+        clock: &C,
+    ) -> EorzeaWeather {
+        self.calculate_forecast(zone, clock.now(), 0)
+    }
+
+    /// Finds the next time the weather of a zone changes
+    /// See `Forecaster::find_next_weather_change`
+    ///
+    /// # Arguments
+    /// - `zone` - The name or `ZoneId` of the zone
+    /// - `clock` - The source of the current time
+    /// - `horizon` - How far ahead to search
+    ///
+    /// # Returns
+    /// - The next change, or `None` if the weather does not change within the horizon
+    pub fn next_weather_change<Z: ZoneKey, C: Clock + ?Sized>(
+        &self,
+        zone: Z,
+        clock: &C,
+        horizon: SearchHorizon,
+    ) -> Option<WeatherChange> {
+        self.find_next_weather_change(zone, clock.now(), horizon)
+    }
+}
@@ -0,0 +1,412 @@
+use crate::eorzean_weather::Weather;
+use crate::weather_data::{WeatherDataError, WeatherTable, ZoneId, ZoneWeather};
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Errors that can occur while importing the datamined sheets
+#[derive(Debug)]
+pub enum ImportError {
+    /// A sheet could not be read
+    Io { path: PathBuf, error: io::Error },
+    /// A sheet does not have the header rows of a datamined CSV
+    MissingHeader { sheet: String },
+    /// A sheet does not have a column the importer needs
+    MissingColumn { sheet: String, column: String },
+    /// A cell that should hold a number does not
+    InvalidNumber {
+        sheet: String,
+        row: String,
+        column: String,
+        value: String,
+    },
+    /// The imported zones could not form a weather table
+    Table(WeatherDataError),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Io { path, error } => {
+                write!(f, "Unable to read '{}': {}", path.display(), error)
+            }
+            ImportError::MissingHeader { sheet } => {
+                write!(f, "{} is missing its header rows", sheet)
+            }
+            ImportError::MissingColumn { sheet, column } => {
+                write!(f, "{} is missing the '{}' column", sheet, column)
+            }
+            ImportError::InvalidNumber {
+                sheet,
+                row,
+                column,
+                value,
+            } => write!(
+                f,
+                "{} row {} has an invalid '{}' value '{}'",
+                sheet, row, column, value
+            ),
+            ImportError::Table(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ImportError::Io { error, .. } => Some(error),
+            ImportError::Table(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// A territory that was left out of the imported table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedTerritory {
+    pub territory_id: u32,
+    pub name: String,
+    pub reason: String,
+}
+
+/// The result of importing the datamined sheets
+#[derive(Debug, Clone)]
+pub struct DatamineImport {
+    /// The regenerated weather table, keyed by TerritoryType id
+    pub table: WeatherTable,
+    /// Territories with a weather rate that could not be imported
+    pub skipped: Vec<SkippedTerritory>,
+}
+
+/// A zone whose name differs between two weather tables
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZoneRename {
+    pub id: ZoneId,
+    pub old_name: String,
+    pub new_name: String,
+}
+
+/// The rates of a zone that differ between two weather tables
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoneRateChange {
+    pub id: ZoneId,
+    /// The name of the zone in the new table
+    pub name: String,
+    pub old_rates: Vec<(Weather, i32)>,
+    pub new_rates: Vec<(Weather, i32)>,
+}
+
+/// The differences between two weather tables, compared by zone id
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WeatherTableDiff {
+    /// The ids and names of the zones only in the new table
+    pub added: Vec<(ZoneId, String)>,
+    /// The ids and names of the zones only in the old table
+    pub removed: Vec<(ZoneId, String)>,
+    pub renamed: Vec<ZoneRename>,
+    pub changed: Vec<ZoneRateChange>,
+}
+
+impl WeatherTableDiff {
+    /// Checks whether the two tables have the same zones, names and rates
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.renamed.is_empty()
+            && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for WeatherTableDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No changes");
+        }
+        for (id, name) in &self.added {
+            writeln!(f, "+ {} {}", id, name)?;
+        }
+        for (id, name) in &self.removed {
+            writeln!(f, "- {} {}", id, name)?;
+        }
+        for rename in &self.renamed {
+            writeln!(
+                f,
+                "> {} {} -> {}",
+                rename.id, rename.old_name, rename.new_name
+            )?;
+        }
+        for change in &self.changed {
+            writeln!(f, "~ {} {}", change.id, change.name)?;
+            writeln!(f, "    old: {}", format_rates(&change.old_rates))?;
+            writeln!(f, "    new: {}", format_rates(&change.new_rates))?;
+        }
+        Ok(())
+    }
+}
+
+// Formats rates as "Clouds 20, Clear Skies 50, ..."
+fn format_rates(rates: &[(Weather, i32)]) -> String {
+    rates
+        .iter()
+        .map(|(weather, chance)| format!("{} {}", weather, chance))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+// A datamined sheet: column names from the second header row, and the data rows
+struct Sheet {
+    name: String,
+    columns: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Sheet {
+    fn read(directory: &Path, name: &str) -> Result<Sheet, ImportError> {
+        let path = directory.join(format!("{}.csv", name));
+        let contents =
+            fs::read_to_string(&path).map_err(|error| ImportError::Io { path, error })?;
+        Sheet::parse(name, &contents)
+    }
+
+    fn parse(name: &str, contents: &str) -> Result<Sheet, ImportError> {
+        // Datamined sheets have three header rows: column indices, column names and column types
+        let mut records = parse_csv(contents.trim_start_matches('\u{feff}')).into_iter();
+        let columns = records.nth(1).ok_or_else(|| ImportError::MissingHeader {
+            sheet: name.to_string(),
+        })?;
+        if records.next().is_none() {
+            return Err(ImportError::MissingHeader {
+                sheet: name.to_string(),
+            });
+        }
+        Ok(Sheet {
+            name: name.to_string(),
+            columns,
+            rows: records
+                .filter(|row| row.iter().any(|cell| !cell.is_empty()))
+                .collect(),
+        })
+    }
+
+    fn column(&self, column: &str) -> Result<usize, ImportError> {
+        self.columns
+            .iter()
+            .position(|name| name == column)
+            .ok_or_else(|| ImportError::MissingColumn {
+                sheet: self.name.clone(),
+                column: column.to_string(),
+            })
+    }
+
+    fn number(&self, row: &[String], column: usize) -> Result<u32, ImportError> {
+        let value = row.get(column).map(String::as_str).unwrap_or("");
+        value
+            .trim()
+            .parse()
+            .map_err(|_| ImportError::InvalidNumber {
+                sheet: self.name.clone(),
+                row: row.first().cloned().unwrap_or_default(),
+                column: self.columns[column].clone(),
+                value: value.to_string(),
+            })
+    }
+
+    // Maps the row key (first column) to the value of another column
+    fn strings_by_key(&self, column: &str) -> Result<HashMap<u32, String>, ImportError> {
+        let column = self.column(column)?;
+        let mut values = HashMap::new();
+        for row in &self.rows {
+            values.insert(
+                self.number(row, 0)?,
+                row.get(column).cloned().unwrap_or_default(),
+            );
+        }
+        Ok(values)
+    }
+}
+
+// Splits CSV text into records, handling quoted fields with escaped quotes and line breaks
+fn parse_csv(contents: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = contents.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => record.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+/// Regenerates the weather table from the community datamined sheets
+/// Every territory with a place name becomes a zone keyed by its TerritoryType id. Territories sharing
+/// both a place name and rates are only imported once, and aliases are carried over from the zone with
+/// the same id in `aliases_from`.
+///
+/// # Arguments
+/// - `directory` - A directory containing WeatherRate.csv, Weather.csv, TerritoryType.csv and PlaceName.csv
+/// - `aliases_from` - The table to copy zone aliases from, usually `WeatherTable::builtin()`
+///
+/// # Returns
+/// - The imported table and the territories that were skipped, or an error if a sheet is missing or malformed
+pub fn import_datamined_sheets<P: AsRef<Path>>(
+    directory: P,
+    aliases_from: &WeatherTable,
+) -> Result<DatamineImport, ImportError> {
+    let directory = directory.as_ref();
+    let weather_rates = Sheet::read(directory, "WeatherRate")?;
+    let weathers = Sheet::read(directory, "Weather")?;
+    let territories = Sheet::read(directory, "TerritoryType")?;
+    let place_names = Sheet::read(directory, "PlaceName")?;
+
+    let weather_names = weathers.strings_by_key("Name")?;
+    let place_names = place_names.strings_by_key("Name")?;
+
+    // Resolve each weather rate to its cumulative chances, keeping the unknown weather names for reporting
+    let mut rates_by_id: HashMap<u32, Result<Vec<(Weather, i32)>, String>> = HashMap::new();
+    for row in &weather_rates.rows {
+        let mut rates = Ok(Vec::new());
+        let mut cumulative_chance = 0;
+        for slot in 0.. {
+            let (Ok(weather_column), Ok(rate_column)) = (
+                weather_rates.column(&format!("Weather[{}]", slot)),
+                weather_rates.column(&format!("Rate[{}]", slot)),
+            ) else {
+                break;
+            };
+            let weather_id = weather_rates.number(row, weather_column)?;
+            let rate = weather_rates.number(row, rate_column)? as i32;
+            if rate == 0 {
+                continue;
+            }
+            cumulative_chance += rate;
+            let weather_name = weather_names.get(&weather_id).cloned().unwrap_or_default();
+            if let Ok(entries) = &mut rates {
+                match weather_name.parse::<Weather>() {
+                    Ok(weather) => entries.push((weather, cumulative_chance)),
+                    Err(_) => rates = Err(format!("unknown weather '{}'", weather_name)),
+                }
+            }
+        }
+        rates_by_id.insert(weather_rates.number(row, 0)?, rates);
+    }
+
+    let place_name_column = territories.column("PlaceName")?;
+    let weather_rate_column = territories.column("WeatherRate")?;
+    let mut zones: Vec<ZoneWeather> = Vec::new();
+    let mut skipped = Vec::new();
+    for row in &territories.rows {
+        let territory_id = territories.number(row, 0)?;
+        let place_name_id = territories.number(row, place_name_column)?;
+        let weather_rate_id = territories.number(row, weather_rate_column)?;
+        let name = match place_names.get(&place_name_id) {
+            Some(name) if !name.is_empty() => name.clone(),
+            _ => continue,
+        };
+        let rates = match rates_by_id.get(&weather_rate_id) {
+            Some(Ok(rates)) if !rates.is_empty() => rates.clone(),
+            Some(Ok(_)) => continue,
+            Some(Err(reason)) => {
+                skipped.push(SkippedTerritory {
+                    territory_id,
+                    name,
+                    reason: reason.clone(),
+                });
+                continue;
+            }
+            None => {
+                skipped.push(SkippedTerritory {
+                    territory_id,
+                    name,
+                    reason: format!("unknown weather rate {}", weather_rate_id),
+                });
+                continue;
+            }
+        };
+        if zones
+            .iter()
+            .any(|zone| zone.name == name && zone.rates == rates)
+        {
+            continue;
+        }
+        let aliases = aliases_from
+            .zone_by_id(ZoneId(territory_id))
+            .map(|zone| zone.aliases.clone())
+            .unwrap_or_default();
+        zones.push(ZoneWeather {
+            id: ZoneId(territory_id),
+            name,
+            aliases,
+            rates,
+        });
+    }
+
+    Ok(DatamineImport {
+        table: WeatherTable::from_zones(zones).map_err(ImportError::Table)?,
+        skipped,
+    })
+}
+
+/// Compares two weather tables zone by zone, matching zones by id
+///
+/// # Arguments
+/// - `old` - The current table, usually `WeatherTable::builtin()`
+/// - `new` - The regenerated table
+///
+/// # Returns
+/// - The zones that were added, removed, renamed or whose rates changed, each sorted by id
+pub fn diff_weather_tables(old: &WeatherTable, new: &WeatherTable) -> WeatherTableDiff {
+    let old_zones: BTreeMap<ZoneId, &ZoneWeather> =
+        old.zones().iter().map(|zone| (zone.id, zone)).collect();
+    let new_zones: BTreeMap<ZoneId, &ZoneWeather> =
+        new.zones().iter().map(|zone| (zone.id, zone)).collect();
+
+    let mut diff = WeatherTableDiff::default();
+    for (id, old_zone) in &old_zones {
+        let Some(new_zone) = new_zones.get(id) else {
+            diff.removed.push((*id, old_zone.name.clone()));
+            continue;
+        };
+        if new_zone.name != old_zone.name {
+            diff.renamed.push(ZoneRename {
+                id: *id,
+                old_name: old_zone.name.clone(),
+                new_name: new_zone.name.clone(),
+            });
+        }
+        if new_zone.rates != old_zone.rates {
+            diff.changed.push(ZoneRateChange {
+                id: *id,
+                name: new_zone.name.clone(),
+                old_rates: old_zone.rates.clone(),
+                new_rates: new_zone.rates.clone(),
+            });
+        }
+    }
+    diff.added = new_zones
+        .iter()
+        .filter(|(id, _)| !old_zones.contains_key(*id))
+        .map(|(id, zone)| (*id, zone.name.clone()))
+        .collect();
+    diff
+}
@@ -1,4 +1,6 @@
-use chrono::{DateTime,Utc};
+use crate::time_window_set::TimeWindowSet;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
 
 const EORZEA_CONSTANT: f64 = 3600.0 / 175.0;
 
@@ -36,10 +38,8 @@ pub const EORZEA_MOON_PHASES: [&str; 8] = [
     "Waning Crescent",
 ];
 
-pub const EORZEA_MOON_CYCLE_PREFIX: [&str; 6] = [
-    "First", "Second", "Third", "Fourth", "Fifth", "Sixth"
-];
-
+pub const EORZEA_MOON_CYCLE_PREFIX: [&str; 6] =
+    ["First", "Second", "Third", "Fourth", "Fifth", "Sixth"];
 
 #[derive(Debug)]
 pub struct EorzeanTime {
@@ -68,7 +68,7 @@ pub trait ToUnixTimestamp {
     /// Converts the implementing type to a Unix timestamp.
     ///
     /// # Returns
-    /// 
+    ///
     /// An `i64` representing the Unix timestamp
     fn to_unix_timestamp(&self) -> i64;
 }
@@ -87,16 +87,100 @@ impl ToUnixTimestamp for DateTime<Utc> {
     }
 }
 
+// Converts a Unix timestamp to a DateTime, panicking if it is outside the range chrono supports
+pub(crate) fn timestamp_to_datetime(timestamp: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_else(|| panic!("The timestamp {} is out of range for a DateTime", timestamp))
+}
+
+/// A span of Earth time, such as a weather interval
+/// Provides typed accessors on top of the Unix timestamps of the start and end
+pub trait EarthTimeSpan {
+    /// Gets the start of the span as a Unix timestamp (inclusive)
+    fn start_timestamp(&self) -> i64;
+
+    /// Gets the end of the span as a Unix timestamp (exclusive)
+    fn end_timestamp(&self) -> i64;
+
+    /// Gets the start of the span as a `DateTime<Utc>`
+    fn start_datetime(&self) -> DateTime<Utc> {
+        timestamp_to_datetime(self.start_timestamp())
+    }
+
+    /// Gets the end of the span as a `DateTime<Utc>`
+    fn end_datetime(&self) -> DateTime<Utc> {
+        timestamp_to_datetime(self.end_timestamp())
+    }
+
+    /// Gets the start of the span in a time zone
+    fn start_datetime_in<Tz: TimeZone>(&self, time_zone: &Tz) -> DateTime<Tz> {
+        self.start_datetime().with_timezone(time_zone)
+    }
+
+    /// Gets the end of the span in a time zone
+    fn end_datetime_in<Tz: TimeZone>(&self, time_zone: &Tz) -> DateTime<Tz> {
+        self.end_datetime().with_timezone(time_zone)
+    }
+
+    /// Gets the length of the span
+    fn duration(&self) -> Duration {
+        Duration::seconds(self.end_timestamp() - self.start_timestamp())
+    }
+
+    /// Gets the time left until the end of the span
+    ///
+    /// # Arguments
+    /// - `current_time` - The instant to measure from
+    ///
+    /// # Returns
+    /// - The time until the end, which includes the wait for the start if the span has not started yet,
+    ///   or zero if the span has ended
+    fn remaining_from<T: ToUnixTimestamp>(&self, current_time: T) -> Duration {
+        Duration::seconds((self.end_timestamp() - current_time.to_unix_timestamp()).max(0))
+    }
+
+    /// Checks whether an instant is within the span
+    fn contains_time<T: ToUnixTimestamp>(&self, time: T) -> bool {
+        let timestamp = time.to_unix_timestamp();
+        self.start_timestamp() <= timestamp && timestamp < self.end_timestamp()
+    }
+
+    /// Gets the Eorzean date at the start of the span
+    fn start_eorzean_date(&self) -> EorzeanDate {
+        convert_to_eorzean_date(self.start_timestamp())
+    }
+
+    /// Gets the Eorzean date at the end of the span
+    fn end_eorzean_date(&self) -> EorzeanDate {
+        convert_to_eorzean_date(self.end_timestamp())
+    }
+}
+
+impl EarthTimeSpan for (i64, i64) {
+    fn start_timestamp(&self) -> i64 {
+        self.0
+    }
+
+    fn end_timestamp(&self) -> i64 {
+        self.1
+    }
+}
+
 /// Calculates the Eorzean time given a Unix timestamp
-/// 
+///
 /// # Arguments
 /// - `input_time` - A type that implements the `ToUnixTimestamp` trait (i64 or chrono::DateTime<Utc>)
-/// 
+///
 /// # Returns
 /// - An EorzeanDate struct representing the Eorzean time equivalent of the input time
+#[allow(
+    clippy::manual_is_multiple_of,
+    clippy::redundant_field_names,
+    clippy::unnecessary_cast
+)]
 pub fn convert_to_eorzean_date<T: ToUnixTimestamp>(input_time: T) -> EorzeanDate {
     let local_epoch = input_time.to_unix_timestamp();
-    
+
     let epoch = local_epoch as f64 * EORZEA_CONSTANT;
     let minutes = (epoch / EORZEA_SECONDS_PER_MINUTE) % EORZEA_SECONDS_PER_MINUTE;
     let bells = (epoch / EORZEA_SECONDS_PER_HOUR) % 24.0;
@@ -104,17 +188,17 @@ pub fn convert_to_eorzean_date<T: ToUnixTimestamp>(input_time: T) -> EorzeanDate
     let year = total_suns / (32 * 12);
     let moon_idx = (total_suns / 32) % 12;
     let mut moon_str = EORZEA_MOON_CYCLE_PREFIX[(moon_idx / 2) as usize].to_string();
-    
+
     if (moon_idx + 1) % 2 == 0 {
         moon_str.push_str(" Umbral Moon");
     } else {
         moon_str.push_str(" Astral Moon");
     }
-    
+
     let sun = total_suns % 32 + 1;
     let guardian = EORZEA_THE_TWELVE[(moon_idx) as usize].to_string();
-    let moon_phase = EORZEA_MOON_PHASES[(sun / 4) as usize].to_string();
-    
+    let moon_phase = EORZEA_MOON_PHASES[moon_phase_index(sun)].to_string();
+
     EorzeanDate {
         guardian: guardian,
         phase: moon_phase,
@@ -125,20 +209,58 @@ pub fn convert_to_eorzean_date<T: ToUnixTimestamp>(input_time: T) -> EorzeanDate
         minutes: minutes as u64,
     }
 }
+
+// Gets the index in `EORZEA_MOON_PHASES` of the phase on a sun of the moon (1 to 32), each phase lasts four suns
+fn moon_phase_index(sun: u64) -> usize {
+    ((sun - 1) / 4) as usize
+}
+
+/// Finds the Earth time windows during which the moon is in a phase
+///
+/// # Arguments
+/// - `phase` - The index of the phase in `EORZEA_MOON_PHASES`
+/// - `start_time` - Start of the Earth time span to search (inclusive)
+/// - `end_time` - End of the Earth time span to search (exclusive)
+///
+/// # Returns
+/// - The windows of the phase clipped to the span, or an empty set if the phase index is out of range
+pub fn moon_phase_window_set<T: ToUnixTimestamp>(
+    phase: usize,
+    start_time: T,
+    end_time: T,
+) -> TimeWindowSet {
+    let start_time = start_time.to_unix_timestamp();
+    let end_time = end_time.to_unix_timestamp();
+    if phase >= EORZEA_MOON_PHASES.len() || end_time <= start_time {
+        return TimeWindowSet::new();
+    }
+    let earth_seconds_per_moon = 32 * EARTH_SECONDS_PER_EORZEAN_DAY;
+    let earth_seconds_per_phase = 4 * EARTH_SECONDS_PER_EORZEAN_DAY;
+    let windows: TimeWindowSet = (start_time.div_euclid(earth_seconds_per_moon)
+        ..=(end_time - 1).div_euclid(earth_seconds_per_moon))
+        .map(|moon| {
+            let phase_start =
+                moon * earth_seconds_per_moon + phase as i64 * earth_seconds_per_phase;
+            (phase_start, phase_start + earth_seconds_per_phase)
+        })
+        .collect();
+    windows.clip(start_time, end_time)
+}
+
 /// Converts a Unix timestamp to Eorzean time
 /// A much simpler function to quickly get the current Eorzean time
-/// 
+///
 /// # Arguments
 /// - `input_time` - A type that implements the `ToUnixTimestamp` trait (i64 or chrono::DateTime<Utc>)
-/// 
+///
 /// # Returns
 /// - A tuple containing the bells and minutes of the Eorzean time equivalent of the input time
 pub fn convert_to_eorzean_time<T: ToUnixTimestamp>(input_time: T) -> (u8, u8) {
     let local_epoch = input_time.to_unix_timestamp();
-    
+
     let epoch = local_epoch as f64 * EORZEA_CONSTANT;
     let minutes = (epoch / 60.0) % 60.0;
-    let bells = (epoch /  3600.0) % 24.0;
+    let bells = (epoch / 3600.0) % 24.0;
     (bells as u8, minutes as u8)
 }
 
@@ -146,11 +268,11 @@ pub fn convert_to_eorzean_time<T: ToUnixTimestamp>(input_time: T) -> (u8, u8) {
 ///
 /// # Arguments
 /// - `seconds` - A `f64` representing the number of seconds to convert
-/// 
+///
 /// # Returns
 /// - An `EorzeanTime` struct representing the Eorzean time equivalent of the input seconds
 pub fn earth_sec_to_eorzea_duration(seconds: i64) -> EorzeanTime {
-    let eorzean_seconds = seconds as f64 * 1.0/0.0486;
+    let eorzean_seconds = seconds as f64 * 1.0 / 0.0486;
 
     let years = (eorzean_seconds / EORZEA_SECONDS_PER_YEAR) as u64;
     let remaining_seconds = eorzean_seconds % EORZEA_SECONDS_PER_YEAR;
@@ -189,12 +311,123 @@ pub fn earth_sec_to_eorzea_duration(seconds: i64) -> EorzeanTime {
 /// # Returns
 /// - A `i64` representing the number of Earth seconds corresponding to the Eorzean duration
 pub fn eorzea_duration_to_earth_sec(eorzean_duration: EorzeanTime) -> i64 {
-    let total_seconds = eorzean_duration.years as f64 * EORZEA_SECONDS_PER_YEAR +
-        eorzean_duration.moons as f64 * EORZEA_SECONDS_PER_MOON +
-        eorzean_duration.weeks as f64 * EORZEA_SECONDS_PER_WEEK +
-        eorzean_duration.suns as f64 * EORZEA_SECONDS_PER_SUN +
-        eorzean_duration.bells as f64 * EORZEA_SECONDS_PER_HOUR +
-        eorzean_duration.minutes as f64 * EORZEA_SECONDS_PER_MINUTE +
-        eorzean_duration.seconds as f64;
-    (total_seconds * (35.0/720.0)) as i64
+    (eorzean_duration_seconds(&eorzean_duration) * (35.0 / 720.0)) as i64
+}
+
+// Gets the total number of Eorzean seconds in an Eorzean duration
+pub(crate) fn eorzean_duration_seconds(eorzean_duration: &EorzeanTime) -> f64 {
+    eorzean_duration.years as f64 * EORZEA_SECONDS_PER_YEAR
+        + eorzean_duration.moons as f64 * EORZEA_SECONDS_PER_MOON
+        + eorzean_duration.weeks as f64 * EORZEA_SECONDS_PER_WEEK
+        + eorzean_duration.suns as f64 * EORZEA_SECONDS_PER_SUN
+        + eorzean_duration.bells as f64 * EORZEA_SECONDS_PER_HOUR
+        + eorzean_duration.minutes as f64 * EORZEA_SECONDS_PER_MINUTE
+        + eorzean_duration.seconds as f64
+}
+
+/// Earth seconds in an Eorzean day (sun)
+pub const EARTH_SECONDS_PER_EORZEAN_DAY: i64 = 4200;
+
+/// A range of Eorzean time of day, such as 08:00 to 12:00
+/// The start is inclusive and the end exclusive. A range whose end is before its start wraps past midnight,
+/// and a range whose start and end are equal covers the whole day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EorzeanTimeRange {
+    /// Start of the range as (bells, minutes)
+    pub start: (u8, u8),
+    /// End of the range as (bells, minutes)
+    pub end: (u8, u8),
+}
+
+impl EorzeanTimeRange {
+    /// Creates a range of Eorzean time of day
+    ///
+    /// # Arguments
+    /// - `start` - Start of the range as (bells, minutes)
+    /// - `end` - End of the range as (bells, minutes), 24:00 is accepted as midnight
+    pub fn new(start: (u8, u8), end: (u8, u8)) -> EorzeanTimeRange {
+        EorzeanTimeRange { start, end }
+    }
+
+    // Minutes since Eorzean midnight of the start and end
+    fn minutes_of_day(&self) -> (i64, i64) {
+        let to_minutes =
+            |(bells, minutes): (u8, u8)| (bells as i64 * 60 + minutes as i64) % (24 * 60);
+        (to_minutes(self.start), to_minutes(self.end))
+    }
+
+    /// Checks whether an Eorzean time of day is within the range
+    ///
+    /// # Arguments
+    /// - `eorzean_time` - A time of day as (bells, minutes), as returned by `convert_to_eorzean_time`
+    pub fn contains(&self, eorzean_time: (u8, u8)) -> bool {
+        let (start, end) = self.minutes_of_day();
+        let minute = eorzean_time.0 as i64 * 60 + eorzean_time.1 as i64;
+        if start < end {
+            start <= minute && minute < end
+        } else {
+            start == end || minute >= start || minute < end
+        }
+    }
+
+    /// Finds the Earth time windows during which the Eorzean time of day is within the range
+    ///
+    /// # Arguments
+    /// - `start_time` - Start of the Earth time span to search (inclusive)
+    /// - `end_time` - End of the Earth time span to search (exclusive)
+    ///
+    /// # Returns
+    /// - A Vec of (start, end) Earth times in chronological order, clipped to the span. Each start is the first
+    ///   whole second whose Eorzean time is in the range, and each end the first whole second after it that is not
+    pub fn earth_windows<T: ToUnixTimestamp>(&self, start_time: T, end_time: T) -> Vec<(i64, i64)> {
+        let start_time = start_time.to_unix_timestamp();
+        let end_time = end_time.to_unix_timestamp();
+        let (start_minute, end_minute) = self.minutes_of_day();
+        // First whole Earth second at or after an Eorzean minute of the day, one Eorzean minute is 35/12 Earth seconds
+        let earth_offset = |minute: i64| (minute * 35 + 11) / 12;
+        let mut windows: Vec<(i64, i64)> = Vec::new();
+        let first_day = start_time.div_euclid(EARTH_SECONDS_PER_EORZEAN_DAY);
+        let last_day = (end_time - 1).div_euclid(EARTH_SECONDS_PER_EORZEAN_DAY);
+        for day in first_day..=last_day {
+            let day_start = day * EARTH_SECONDS_PER_EORZEAN_DAY;
+            let day_end = day_start + EARTH_SECONDS_PER_EORZEAN_DAY;
+            let segments = if start_minute < end_minute {
+                vec![(
+                    day_start + earth_offset(start_minute),
+                    day_start + earth_offset(end_minute),
+                )]
+            } else if start_minute == end_minute {
+                vec![(day_start, day_end)]
+            } else {
+                vec![
+                    (day_start, day_start + earth_offset(end_minute)),
+                    (day_start + earth_offset(start_minute), day_end),
+                ]
+            };
+            for (segment_start, segment_end) in segments {
+                let window = (segment_start.max(start_time), segment_end.min(end_time));
+                if window.0 >= window.1 {
+                    continue;
+                }
+                // Join windows that continue past midnight into the next day
+                match windows.last_mut() {
+                    Some(last) if last.1 == window.0 => last.1 = window.1,
+                    _ => windows.push(window),
+                }
+            }
+        }
+        windows
+    }
+
+    /// Finds the Earth time windows during which the Eorzean time of day is within the range, as a set
+    /// See `earth_windows`
+    pub fn earth_window_set<T: ToUnixTimestamp>(
+        &self,
+        start_time: T,
+        end_time: T,
+    ) -> TimeWindowSet {
+        self.earth_windows(start_time, end_time)
+            .into_iter()
+            .collect()
+    }
 }
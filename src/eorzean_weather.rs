@@ -1,20 +1,31 @@
-use crate::eorzean_time::convert_to_eorzean_time;
-use crate::eorzean_time::eorzea_duration_to_earth_sec;
-use crate::eorzean_time::EorzeanTime;
-use crate::eorzean_time::ToUnixTimestamp;
+use crate::eorzean_time::{
+    convert_to_eorzean_date, timestamp_to_datetime, EarthTimeSpan, EorzeanDate, EorzeanTimeRange,
+    ToUnixTimestamp,
+};
 
+use crate::forecaster::Forecaster;
+use crate::time_window_set::TimeWindowSet;
+use crate::weather_data::{WeatherDatabase, WeatherTable, ZoneId, ZoneKey, ZoneWeather};
+
+use chrono::{DateTime, Duration, Utc};
 use once_cell::sync::Lazy;
+use serde::{Deserialize, Deserializer};
+use std::collections::VecDeque;
+use std::fmt;
+use std::ops::RangeBounds;
+use std::str::FromStr;
 use std::sync::RwLock;
 
 // Global weather output offset for timing
 // Helper for adjusting the output of start_time and end_time when calculating weather intervals
-// Should only be used in a single-threaded environment
+// Should only be used in a single-threaded environment, superseded by `Forecaster::with_timing_offset`
 static GLOBAL_WEATHER_TIMING_OFFSET: Lazy<RwLock<i64>> = Lazy::new(|| RwLock::new(0));
 
 // Sets the global weather timing offset
 //
 // # Arguments
 // offset - The offset to set
+#[deprecated(note = "use `Forecaster::with_timing_offset` instead")]
 pub fn set_global_weather_timing_offset(offset: i64) {
     let mut write = GLOBAL_WEATHER_TIMING_OFFSET.write().unwrap();
     *write = offset;
@@ -24,13 +35,14 @@ pub fn set_global_weather_timing_offset(offset: i64) {
 //
 // # Returns
 // The global weather timing offset
+#[deprecated(note = "use `Forecaster::timing_offset` instead")]
 pub fn get_global_weather_timing_offset() -> i64 {
     let read = GLOBAL_WEATHER_TIMING_OFFSET.read().unwrap();
     *read
 }
 
 /// The different weather types in the game
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Weather {
     AstroMagneticStorm,
     Blizzards,
@@ -42,6 +54,7 @@ pub enum Weather {
     Gales,
     Gloom,
     HeatWaves,
+    Hyperelectricity,
     MoonDust,
     Rain,
     Showers,
@@ -54,65 +67,184 @@ pub enum Weather {
 }
 
 impl Weather {
-    pub fn to_string(&self) -> String {
-        match self {
-            Weather::AstroMagneticStorm => "Astro-Magnetic Storms".to_string(),
-            Weather::Blizzards => "Blizzards".to_string(),
-            Weather::ClearSkies => "Clear Skies".to_string(),
-            Weather::Clouds => "Clouds".to_string(),
-            Weather::DustStorms => "Dust Storms".to_string(),
-            Weather::FairSkies => "Fair Skies".to_string(),
-            Weather::Fog => "Fog".to_string(),
-            Weather::Gales => "Gales".to_string(),
-            Weather::Gloom => "Gloom".to_string(),
-            Weather::HeatWaves => "Heat Waves".to_string(),
-            Weather::MoonDust => "Moon Dust".to_string(),
-            Weather::Rain => "Rain".to_string(),
-            Weather::Showers => "Showers".to_string(),
-            Weather::Snow => "Snow".to_string(),
-            Weather::Thunder => "Thunder".to_string(),
-            Weather::Thunderstorms => "Thunderstorms".to_string(),
-            Weather::UmbralStatic => "Umbral Static".to_string(),
-            Weather::UmbralWind => "Umbral Wind".to_string(),
-            Weather::Wind => "Wind".to_string(),
+    /// Every weather type, in alphabetical order
+    pub const ALL: [Weather; 20] = [
+        Weather::AstroMagneticStorm,
+        Weather::Blizzards,
+        Weather::ClearSkies,
+        Weather::Clouds,
+        Weather::DustStorms,
+        Weather::FairSkies,
+        Weather::Fog,
+        Weather::Gales,
+        Weather::Gloom,
+        Weather::HeatWaves,
+        Weather::Hyperelectricity,
+        Weather::MoonDust,
+        Weather::Rain,
+        Weather::Showers,
+        Weather::Snow,
+        Weather::Thunder,
+        Weather::Thunderstorms,
+        Weather::UmbralStatic,
+        Weather::UmbralWind,
+        Weather::Wind,
+    ];
+}
+
+impl fmt::Display for Weather {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Weather::AstroMagneticStorm => "Astro-Magnetic Storms",
+            Weather::Blizzards => "Blizzards",
+            Weather::ClearSkies => "Clear Skies",
+            Weather::Clouds => "Clouds",
+            Weather::DustStorms => "Dust Storms",
+            Weather::FairSkies => "Fair Skies",
+            Weather::Fog => "Fog",
+            Weather::Gales => "Gales",
+            Weather::Gloom => "Gloom",
+            Weather::HeatWaves => "Heat Waves",
+            Weather::Hyperelectricity => "Hyperelectricity",
+            Weather::MoonDust => "Moon Dust",
+            Weather::Rain => "Rain",
+            Weather::Showers => "Showers",
+            Weather::Snow => "Snow",
+            Weather::Thunder => "Thunder",
+            Weather::Thunderstorms => "Thunderstorms",
+            Weather::UmbralStatic => "Umbral Static",
+            Weather::UmbralWind => "Umbral Wind",
+            Weather::Wind => "Wind",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Error returned when a string is not the name of a weather
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWeatherError(pub String);
+
+impl fmt::Display for ParseWeatherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid weather type '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseWeatherError {}
+
+impl FromStr for Weather {
+    type Err = ParseWeatherError;
+
+    fn from_str(weather_name: &str) -> Result<Self, Self::Err> {
+        match weather_name {
+            "Astro-Magnetic Storms" | "Astromagnetic Storms" => Ok(Weather::AstroMagneticStorm),
+            "Blizzards" => Ok(Weather::Blizzards),
+            "Clear Skies" => Ok(Weather::ClearSkies),
+            "Clouds" => Ok(Weather::Clouds),
+            "Dust Storms" => Ok(Weather::DustStorms),
+            "Fair Skies" => Ok(Weather::FairSkies),
+            "Fog" => Ok(Weather::Fog),
+            "Gales" => Ok(Weather::Gales),
+            "Gloom" => Ok(Weather::Gloom),
+            "Heat Waves" => Ok(Weather::HeatWaves),
+            "Hyperelectricity" => Ok(Weather::Hyperelectricity),
+            "Moon Dust" => Ok(Weather::MoonDust),
+            "Rain" => Ok(Weather::Rain),
+            "Showers" => Ok(Weather::Showers),
+            "Snow" => Ok(Weather::Snow),
+            "Thunder" => Ok(Weather::Thunder),
+            "Thunderstorms" => Ok(Weather::Thunderstorms),
+            "Umbral Static" => Ok(Weather::UmbralStatic),
+            "Umbral Wind" => Ok(Weather::UmbralWind),
+            "Wind" => Ok(Weather::Wind),
+            _ => Err(ParseWeatherError(weather_name.to_string())),
         }
     }
 }
 
-fn map_string_to_weather(zone_name: String) -> Weather {
-    match zone_name.as_str() {
-        "Astro-Magnetic Storms" => Weather::AstroMagneticStorm,
-        "Blizzards" => Weather::Blizzards,
-        "Clear Skies" => Weather::ClearSkies,
-        "Clouds" => Weather::Clouds,
-        "Dust Storms" => Weather::DustStorms,
-        "Fair Skies" => Weather::FairSkies,
-        "Fog" => Weather::Fog,
-        "Gales" => Weather::Gales,
-        "Gloom" => Weather::Gloom,
-        "Heat Waves" => Weather::HeatWaves,
-        "Moon Dust" => Weather::MoonDust,
-        "Rain" => Weather::Rain,
-        "Showers" => Weather::Showers,
-        "Snow" => Weather::Snow,
-        "Thunder" => Weather::Thunder,
-        "Thunderstorms" => Weather::Thunderstorms,
-        "Umbral Static" => Weather::UmbralStatic,
-        "Umbral Wind" => Weather::UmbralWind,
-        "Wind" => Weather::Wind,
-        _ => panic!("Invalid weather type"),
-    }
-}
-
-#[derive(Debug)]
+impl<'de> Deserialize<'de> for Weather {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let weather_name = String::deserialize(deserializer)?;
+        weather_name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct EorzeaWeather {
     pub start_time: i64,
     pub end_time: i64,
+    pub zone_id: ZoneId,
     pub zone_name: String,
     pub weather: Weather,
+    /// The weather of the interval before, if the forecaster includes neighbouring weather
+    pub previous_weather: Option<Weather>,
+    /// The weather of the interval after, if the forecaster includes neighbouring weather
+    pub next_weather: Option<Weather>,
+}
+
+impl EarthTimeSpan for EorzeaWeather {
+    fn start_timestamp(&self) -> i64 {
+        self.start_time
+    }
+
+    fn end_timestamp(&self) -> i64 {
+        self.end_time
+    }
+}
+
+// Gets the weather of a zone at a given time
+pub(crate) fn weather_in_zone(zone: &ZoneWeather, epoch: i64) -> Weather {
+    let forecast_target = calculate_weather_forecast_target(epoch);
+    zone.weather_for_target(forecast_target)
+        .expect("No weather found for the forecast target")
+}
+
+/// Earth seconds in a weather interval (8 Eorzean bells)
+pub const EARTH_SECONDS_PER_WEATHER_INTERVAL: i64 = 1400;
+
+/// Calculates the index of the weather interval containing a given time
+/// Interval 0 starts at the Unix epoch, intervals before the epoch have negative indices
+///
+/// # Arguments
+/// - `current_time` - A type that implements the `ToUnixTimestamp` trait (i64 or chrono::DateTime<Utc>)
+///
+/// # Returns
+/// - An `i64` index, consecutive intervals have consecutive indices
+pub fn weather_interval_index<T: ToUnixTimestamp>(current_time: T) -> i64 {
+    current_time
+        .to_unix_timestamp()
+        .div_euclid(EARTH_SECONDS_PER_WEATHER_INTERVAL)
+}
+
+/// Calculates the start and end times of a weather interval from its index
+///
+/// # Arguments
+/// - `interval_index` - The index returned by `weather_interval_index`
+///
+/// # Returns
+/// - A tuple containing the start (inclusive) and end (exclusive) times of the interval
+pub fn weather_interval_bounds(interval_index: i64) -> (i64, i64) {
+    let start_time = interval_index * EARTH_SECONDS_PER_WEATHER_INTERVAL;
+    (start_time, start_time + EARTH_SECONDS_PER_WEATHER_INTERVAL)
+}
+
+/// Calculates the weather interval a number of intervals away from a given time
+///
+/// # Arguments
+/// - `current_time` - The time to count intervals from
+/// - `interval_offset` - The number of intervals to move. +1 means the next interval, -1 means the previous interval
+///
+/// # Returns
+/// - A tuple containing the start (inclusive) and end (exclusive) times of the interval
+pub fn calculate_weather_interval<T: ToUnixTimestamp>(
+    current_time: T,
+    interval_offset: i64,
+) -> (i64, i64) {
+    weather_interval_bounds(weather_interval_index(current_time) + interval_offset)
 }
 
 /// Calculates the current weather interval
+/// Intervals are aligned on 00:00, 08:00 and 16:00 Eorzean time, which are exactly 1400 Earth seconds apart
 ///
 /// # Arguments
 /// - `current_time` - The current time to calculate the forecast for
@@ -120,46 +252,7 @@ pub struct EorzeaWeather {
 /// # Returns
 /// - A tuple containing the start and end times of the current weather interval
 pub fn calculate_current_weather_interval<T: ToUnixTimestamp>(current_time: T) -> (i64, i64) {
-    let current_epoch = current_time.to_unix_timestamp();
-    let current_eorzean_time = convert_to_eorzean_time(current_epoch);
-    let curr_hour = current_eorzean_time.0 as i64;
-    let curr_minute = current_eorzean_time.1 as i64;
-    // Find the nearest 8 Eorzean hour interval (00:00, 08:00, 16:00)
-    let nearest_start_interval_hour = (curr_hour / 8) * 8;
-    let nearest_end_interval_hour = ((curr_hour / 8) + 1) * 8;
-
-    // Find the difference in minutes to the nearest start and end intervals
-    let ezt_minutes_since_start_interval =
-        (curr_hour * 60 + curr_minute) - (nearest_start_interval_hour * 60);
-    let ezt_minutes_to_end_interval = if nearest_end_interval_hour == 24 {
-        (24 * 60) - (curr_hour * 60 + curr_minute)
-    } else {
-        (nearest_end_interval_hour * 60) - (curr_hour * 60 + curr_minute)
-    };
-
-    let seconds_since_start_interval = eorzea_duration_to_earth_sec(EorzeanTime {
-        years: 0,
-        moons: 0,
-        weeks: 0,
-        suns: 0,
-        bells: 0,
-        minutes: ezt_minutes_since_start_interval as u64,
-        seconds: 0,
-    });
-
-    let seconds_to_end_interval = eorzea_duration_to_earth_sec(EorzeanTime {
-        years: 0,
-        moons: 0,
-        weeks: 0,
-        suns: 0,
-        bells: 0,
-        minutes: ezt_minutes_to_end_interval as u64,
-        seconds: 0,
-    });
-    let current_epoch_seconds = current_epoch.to_unix_timestamp();
-    let start_time = current_epoch_seconds - seconds_since_start_interval as i64;
-    let end_time = current_epoch_seconds + seconds_to_end_interval as i64;
-    (start_time, end_time)
+    calculate_weather_interval(current_time, 0)
 }
 
 /// Calculates the magic number used to determine the weather
@@ -172,128 +265,1305 @@ pub fn calculate_current_weather_interval<T: ToUnixTimestamp>(current_time: T) -
 pub fn calculate_weather_forecast_target<T: ToUnixTimestamp>(current_time: T) -> i32 {
     // Calculate magic weather number the game uses. Thanks to ffxiv-datamining
     let unix_seconds = current_time.to_unix_timestamp();
-    let bell = unix_seconds / 175;
-    let increment = (bell + 8 - (bell % 8)) % 24;
-    let total_days = unix_seconds / 4200;
-    let total_days: u32 = total_days.try_into().unwrap_or(0);
-    let calc_base = total_days * 100 + increment as u32;
+    let bell = unix_seconds.div_euclid(175);
+    let increment = (bell + 8 - bell.rem_euclid(8)) % 24;
+    let total_days = unix_seconds.div_euclid(4200);
+    // The game works on 32 bit integers, wrap the same way for times far from the epoch
+    let calc_base = (total_days as u32)
+        .wrapping_mul(100)
+        .wrapping_add(increment as u32);
     let step1 = (calc_base << 11) ^ calc_base;
     let step2 = (step1 >> 8) ^ step1;
     (step2 % 100) as i32
 }
 
-/// Gets the weather for a given zone at a given time
+/// Creates an iterator over the forecast targets of the weather intervals in a range, independent of any zone
+/// The first item is the interval containing `start_time`, the last one is the interval containing `end_time - 1`.
+/// Times are not adjusted by the global weather timing offset.
 ///
 /// # Arguments
-/// - `zone_name` - The name of the zone to calculate the forecast for
+/// - `start_time` - The start of the range (inclusive)
+/// - `end_time` - The end of the range (exclusive)
 ///
 /// # Returns
-/// - A Weather struct representing the current weather
-pub fn get_weather_by_time<T: ToUnixTimestamp>(zone_name: &str, current_time: T) -> Weather {
-    // Load the weather data from the JSON file
-    let weather_data = include_str!("../data/weather_data.json");
-    let weather_data: serde_json::Value =
-        serde_json::from_str(&weather_data).expect("Unable to parse the weather data");
-    let zone_data = weather_data
-        .get(zone_name)
-        .and_then(|zone| zone.as_array())
-        .expect(&format!(
-            "Unable to find the zone '{}' in the weather data",
-            zone_name
-        ));
-
-    // Convert the JSON array to a Vec of (String, i32) tuples
-    let zone_data = zone_data
-        .iter()
-        .map(|entry| {
-            let weather = entry[0]
-                .as_str()
-                .expect("Invalid weather format")
-                .to_string();
-            let chance = entry[1].as_i64().expect("Invalid chance format") as i32;
-            (weather, chance)
-        })
-        .collect::<Vec<(String, i32)>>();
-
-    // Get the current forecast target
-    let epoch = current_time.to_unix_timestamp();
-    let forecast_target = calculate_weather_forecast_target(epoch);
+/// - An iterator of tuples containing the start time of each interval and its forecast target (0-99)
+pub fn forecast_targets<T: ToUnixTimestamp>(start_time: T, end_time: T) -> ForecastTargetIter {
+    let start_epoch = start_time.to_unix_timestamp();
+    let end_epoch = end_time.to_unix_timestamp();
+    ForecastTargetIter {
+        next_interval_index: weather_interval_index(start_epoch),
+        end_interval_index: if end_epoch > start_epoch {
+            weather_interval_index(end_epoch - 1) + 1
+        } else {
+            weather_interval_index(start_epoch)
+        },
+    }
+}
 
-    // Find the weather type that matches the forecast target
-    for (weather, chance) in zone_data {
-        if forecast_target < chance {
-            return map_string_to_weather(weather);
+/// Finds the weather intervals in a range whose forecast target falls in a bucket, independent of any zone
+/// e.g. the targets 0-4 give the 5% weather of every zone whose first rate is 5
+///
+/// # Arguments
+/// - `start_time` - The start of the range (inclusive)
+/// - `end_time` - The end of the range (exclusive)
+/// - `bucket` - The forecast targets to look for, e.g. `0..5` or `95..=99`
+///
+/// # Returns
+/// - A Vec of tuples containing the start time of each matching interval and its forecast target
+pub fn find_forecast_target_intervals<T: ToUnixTimestamp, R: RangeBounds<i32>>(
+    start_time: T,
+    end_time: T,
+    bucket: R,
+) -> Vec<(i64, i32)> {
+    forecast_targets(start_time, end_time)
+        .filter(|(_, forecast_target)| bucket.contains(forecast_target))
+        .collect()
+}
+
+/// An iterator over the forecast targets of consecutive weather intervals
+/// Created by `forecast_targets`
+#[derive(Debug, Clone)]
+pub struct ForecastTargetIter {
+    next_interval_index: i64,
+    end_interval_index: i64,
+}
+
+impl Iterator for ForecastTargetIter {
+    type Item = (i64, i32);
+
+    fn next(&mut self) -> Option<(i64, i32)> {
+        if self.next_interval_index >= self.end_interval_index {
+            return None;
         }
+        let (start_time, _) = weather_interval_bounds(self.next_interval_index);
+        self.next_interval_index += 1;
+        Some((start_time, calculate_weather_forecast_target(start_time)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.end_interval_index - self.next_interval_index).max(0) as usize;
+        (remaining, Some(remaining))
     }
+}
 
-    // Throw an error if no weather found
-    panic!("No weather found for the forecast target");
+impl DoubleEndedIterator for ForecastTargetIter {
+    fn next_back(&mut self) -> Option<(i64, i32)> {
+        if self.next_interval_index >= self.end_interval_index {
+            return None;
+        }
+        self.end_interval_index -= 1;
+        let (start_time, _) = weather_interval_bounds(self.end_interval_index);
+        Some((start_time, calculate_weather_forecast_target(start_time)))
+    }
 }
 
-/// Calculates the weather forecast for a given zone at a given time
+impl ExactSizeIterator for ForecastTargetIter {}
+
+/// Gets the weather for a given zone at a given time, using the bundled weather data
+///
+/// # Arguments
+/// - `zone` - The name or `ZoneId` of the zone to calculate the forecast for
+///
+/// # Returns
+/// - A Weather struct representing the current weather
+pub fn get_weather_by_time<Z: ZoneKey, T: ToUnixTimestamp>(zone: Z, current_time: T) -> Weather {
+    WeatherDatabase::builtin()
+        .forecaster()
+        .get_weather_by_time(zone, current_time)
+}
+
+/// Calculates the weather forecast for a given zone at a given time, using the bundled weather data
 /// Setting interval_offset to 0 will calculate the weather at the given time, as well as the start and end times of the interval
 ///
 /// # Arguments
-/// - `zone_name` - The name of the zone to calculate the forecast for
+/// - `zone` - The name or `ZoneId` of the zone to calculate the forecast for
 /// - `current_time` - The current time to calculate the forecast for
 /// - `offset` - The intervals to calculate the forecast for. +1 means the next interval, -1 means the previous interval
 ///
 /// # Returns
 /// - An EorzeaWeather struct representing the forecasted weather
 ///
-pub fn calculate_forecast<T: ToUnixTimestamp>(
-    zone_name: &str,
+pub fn calculate_forecast<Z: ZoneKey, T: ToUnixTimestamp>(
+    zone: Z,
     current_time: T,
     interval_offset: i32,
 ) -> EorzeaWeather {
-    // Each interval is 8 Eorzean hours. 00:00, 08:00, 16:00 are the start times
-    let current_epoch = current_time.to_unix_timestamp();
-    if interval_offset == 0 {
-        // Calculate the current weather interval
-        let (start_time, end_time) = calculate_current_weather_interval(current_time);
-        let current_weather = get_weather_by_time(zone_name, current_epoch);
-        return EorzeaWeather {
-            start_time: start_time + get_global_weather_timing_offset(),
-            end_time: end_time + get_global_weather_timing_offset(),
-            zone_name: zone_name.to_string(),
-            weather: current_weather,
-        };
-    }
+    WeatherDatabase::builtin()
+        .forecaster()
+        .calculate_forecast(zone, current_time, interval_offset)
+}
 
-    // Find the current interval and get the weather for the start of the interval
-    let current_forecast_interval = calculate_current_weather_interval(current_epoch);
-    // Weather changes every 23 real-world minutes, convert to 60 seconds, adjust for number of intervals seeking
-    let offset_interval_start =
-        current_forecast_interval.0 + (23 * (1 + interval_offset as i64) * 60);
-    let weather_at_offset = get_weather_by_time(zone_name, offset_interval_start);
-    EorzeaWeather {
-        start_time: offset_interval_start - 1380 + get_global_weather_timing_offset(),
-        end_time: offset_interval_start + get_global_weather_timing_offset(),
-        zone_name: zone_name.to_string(),
-        weather: weather_at_offset,
-    }
+/// Calculates the weather forecast for a given zone at a given time along with the weathers before and after it,
+/// using the bundled weather data
+/// See `calculate_forecast` and `Forecaster::with_neighbouring_weather`
+pub fn calculate_forecast_with_neighbours<Z: ZoneKey, T: ToUnixTimestamp>(
+    zone: Z,
+    current_time: T,
+    interval_offset: i32,
+) -> EorzeaWeather {
+    WeatherDatabase::builtin()
+        .forecaster()
+        .with_neighbouring_weather(true)
+        .calculate_forecast(zone, current_time, interval_offset)
 }
 
-/// Find the time which a next Weather effect will occur
+/// Find the time which a next Weather effect will occur, using the bundled weather data
 ///
 /// # Arguments
-/// - `zone_name` - The name of the zone to calculate the forecast for
+/// - `zone` - The name or `ZoneId` of the zone to calculate the forecast for
 /// - `current_time` - The current time to calculate the forecast for
 /// - `target_weather` - The weather effect to search for
 ///
 /// # Returns
 /// - An EorzeaWeather struct representing the next weather effect
-pub fn find_next_weather_occurance<T: ToUnixTimestamp>(
-    zone_name: &str,
+///
+/// # Panics
+/// - If the weather never occurs in the zone
+#[deprecated(
+    note = "use `find_next_weather_occurrence`, which takes a search horizon and returns an Option"
+)]
+pub fn find_next_weather_occurance<Z: ZoneKey, T: ToUnixTimestamp>(
+    zone: Z,
     current_time: T,
     target_weather: Weather,
 ) -> EorzeaWeather {
-    let current_epoch = current_time.to_unix_timestamp();
-    let mut current_interval = 1;
-    let mut next_weather = calculate_forecast(zone_name, current_epoch, current_interval);
-    while next_weather.weather != target_weather {
-        current_interval += 1;
-        next_weather = calculate_forecast(zone_name, current_epoch, current_interval);
-    }
-    next_weather
+    let zone_name = zone.to_string();
+    find_next_weather_occurrence(
+        zone,
+        current_time,
+        target_weather,
+        SearchHorizon::Intervals(u32::MAX),
+    )
+    .unwrap_or_else(|| {
+        panic!(
+            "The weather '{}' never occurs in '{}'",
+            target_weather, zone_name
+        )
+    })
+}
+
+/// Finds the next interval with a given weather, using the bundled weather data
+/// See `Forecaster::find_next_weather_occurrence`
+pub fn find_next_weather_occurrence<Z: ZoneKey, T: ToUnixTimestamp>(
+    zone: Z,
+    current_time: T,
+    target_weather: Weather,
+    horizon: SearchHorizon,
+) -> Option<EorzeaWeather> {
+    WeatherDatabase::builtin()
+        .forecaster()
+        .find_next_weather_occurrence(zone, current_time, target_weather, horizon)
+}
+
+/// Finds the previous interval with a given weather, using the bundled weather data
+/// See `Forecaster::find_previous_weather_occurrence`
+pub fn find_previous_weather_occurrence<Z: ZoneKey, T: ToUnixTimestamp>(
+    zone: Z,
+    current_time: T,
+    target_weather: Weather,
+    horizon: SearchHorizon,
+) -> Option<EorzeaWeather> {
+    WeatherDatabase::builtin()
+        .forecaster()
+        .find_previous_weather_occurrence(zone, current_time, target_weather, horizon)
+}
+
+/// Finds the next interval whose weather matches a filter, using the bundled weather data
+/// See `Forecaster::find_next_matching_weather`
+pub fn find_next_matching_weather<Z: ZoneKey, T: ToUnixTimestamp>(
+    zone: Z,
+    current_time: T,
+    filter: &WeatherFilter,
+    horizon: SearchHorizon,
+) -> Option<EorzeaWeather> {
+    WeatherDatabase::builtin()
+        .forecaster()
+        .find_next_matching_weather(zone, current_time, filter, horizon)
+}
+
+/// Finds the previous interval whose weather matches a filter, using the bundled weather data
+/// See `Forecaster::find_previous_matching_weather`
+pub fn find_previous_matching_weather<Z: ZoneKey, T: ToUnixTimestamp>(
+    zone: Z,
+    current_time: T,
+    filter: &WeatherFilter,
+    horizon: SearchHorizon,
+) -> Option<EorzeaWeather> {
+    WeatherDatabase::builtin()
+        .forecaster()
+        .find_previous_matching_weather(zone, current_time, filter, horizon)
+}
+
+/// Finds the next intervals where the weather changes from one set of weathers to another, using the bundled weather data
+/// See `Forecaster::find_weather_transitions`
+pub fn find_weather_transitions<Z: ZoneKey, T: ToUnixTimestamp>(
+    zone: Z,
+    current_time: T,
+    previous: &WeatherFilter,
+    current: &WeatherFilter,
+    count: usize,
+    horizon: SearchHorizon,
+) -> Vec<WeatherTransition> {
+    WeatherDatabase::builtin()
+        .forecaster()
+        .find_weather_transitions(zone, current_time, previous, current, count, horizon)
+}
+
+/// Finds the windows where a zone's weather matches a filter during a range of Eorzean time, using the bundled weather data
+/// See `Forecaster::find_weather_time_windows`
+pub fn find_weather_time_windows<Z: ZoneKey, T: ToUnixTimestamp>(
+    zone: Z,
+    current_time: T,
+    filter: &WeatherFilter,
+    time_range: &EorzeanTimeRange,
+    count: usize,
+    horizon: SearchHorizon,
+) -> Vec<WeatherWindow> {
+    WeatherDatabase::builtin()
+        .forecaster()
+        .find_weather_time_windows(zone, current_time, filter, time_range, count, horizon)
+}
+
+/// Finds the times a zone's weather matches a filter within a span, using the bundled weather data
+/// See `Forecaster::weather_window_set`
+pub fn weather_window_set<Z: ZoneKey, T: ToUnixTimestamp>(
+    zone: Z,
+    filter: &WeatherFilter,
+    start_time: T,
+    end_time: T,
+) -> TimeWindowSet {
+    WeatherDatabase::builtin()
+        .forecaster()
+        .weather_window_set(zone, filter, start_time, end_time)
+}
+
+/// Finds the next run of consecutive intervals matching a filter, using the bundled weather data
+/// See `Forecaster::find_next_weather_run`
+pub fn find_next_weather_run<Z: ZoneKey, T: ToUnixTimestamp>(
+    zone: Z,
+    current_time: T,
+    filter: &WeatherFilter,
+    min_length: usize,
+    horizon: SearchHorizon,
+) -> Option<WeatherRun> {
+    WeatherDatabase::builtin()
+        .forecaster()
+        .find_next_weather_run(zone, current_time, filter, min_length, horizon)
+}
+
+/// Finds the longest run of consecutive intervals matching a filter, using the bundled weather data
+/// See `Forecaster::find_longest_weather_run`
+pub fn find_longest_weather_run<Z: ZoneKey, T: ToUnixTimestamp>(
+    zone: Z,
+    current_time: T,
+    filter: &WeatherFilter,
+    horizon: SearchHorizon,
+) -> Option<WeatherRun> {
+    WeatherDatabase::builtin()
+        .forecaster()
+        .find_longest_weather_run(zone, current_time, filter, horizon)
+}
+
+/// Gets the chance of each weather in a zone, using the bundled weather data
+/// See `WeatherDatabase::weather_chances`
+pub fn weather_chances<Z: ZoneKey>(zone: Z) -> Vec<(Weather, i32)> {
+    WeatherDatabase::builtin().weather_chances(zone)
+}
+
+/// Finds the zones where a weather can occur, using the bundled weather data
+/// See `WeatherDatabase::zones_with_weather`
+pub fn zones_with_weather(weather: Weather) -> Vec<(&'static ZoneWeather, i32)> {
+    WeatherDatabase::builtin().zones_with_weather(weather)
+}
+
+/// Gets the previous, current and upcoming weather of every zone, using the bundled weather data
+/// See `Forecaster::weather_snapshot`
+pub fn weather_snapshot<T: ToUnixTimestamp>(
+    current_time: T,
+    upcoming_count: usize,
+) -> WeatherSnapshot {
+    WeatherDatabase::builtin()
+        .forecaster()
+        .weather_snapshot(current_time, upcoming_count)
+}
+
+/// Gets the previous, current and upcoming weather of a set of zones, using the bundled weather data
+/// See `Forecaster::weather_snapshot_for`
+pub fn weather_snapshot_for<Z: ZoneKey, T: ToUnixTimestamp>(
+    zones: &[Z],
+    current_time: T,
+    upcoming_count: usize,
+) -> WeatherSnapshot {
+    WeatherDatabase::builtin()
+        .forecaster()
+        .weather_snapshot_for(zones, current_time, upcoming_count)
+}
+
+/// The weather of several zones around a single point in time
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherSnapshot {
+    /// The time the snapshot was taken for
+    pub time: i64,
+    pub zones: Vec<ZoneWeatherSnapshot>,
+}
+
+impl WeatherSnapshot {
+    /// Finds the snapshot of a zone by its id
+    pub fn zone_by_id(&self, id: ZoneId) -> Option<&ZoneWeatherSnapshot> {
+        self.zones.iter().find(|zone| zone.zone_id == id)
+    }
+
+    /// Finds the snapshot of a zone by its exact name, the last one if several zones share the name
+    pub fn zone_by_name(&self, name: &str) -> Option<&ZoneWeatherSnapshot> {
+        self.zones.iter().rev().find(|zone| zone.zone_name == name)
+    }
+}
+
+/// The previous, current and upcoming weather of a zone
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoneWeatherSnapshot {
+    pub zone_id: ZoneId,
+    pub zone_name: String,
+    pub previous: EorzeaWeather,
+    pub current: EorzeaWeather,
+    /// The intervals after the current one in chronological order
+    pub upcoming: Vec<EorzeaWeather>,
+}
+
+/// Consecutive weather intervals of a zone that all match a filter
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherRun {
+    /// The start of the first interval
+    pub start_time: i64,
+    /// The end of the last interval
+    pub end_time: i64,
+    /// The intervals of the run in chronological order
+    pub forecasts: Vec<EorzeaWeather>,
+}
+
+impl WeatherRun {
+    fn from_forecasts(forecasts: Vec<EorzeaWeather>) -> WeatherRun {
+        WeatherRun {
+            start_time: forecasts.first().map_or(0, |forecast| forecast.start_time),
+            end_time: forecasts.last().map_or(0, |forecast| forecast.end_time),
+            forecasts,
+        }
+    }
+
+    /// The number of intervals in the run
+    pub fn length(&self) -> usize {
+        self.forecasts.len()
+    }
+}
+
+impl EarthTimeSpan for WeatherRun {
+    fn start_timestamp(&self) -> i64 {
+        self.start_time
+    }
+
+    fn end_timestamp(&self) -> i64 {
+        self.end_time
+    }
+}
+
+/// The part of a weather interval that falls within a range of Eorzean time
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherWindow {
+    pub start_time: i64,
+    pub end_time: i64,
+    /// The weather interval the window is part of
+    pub forecast: EorzeaWeather,
+}
+
+impl EarthTimeSpan for WeatherWindow {
+    fn start_timestamp(&self) -> i64 {
+        self.start_time
+    }
+
+    fn end_timestamp(&self) -> i64 {
+        self.end_time
+    }
+}
+
+/// Two consecutive weather intervals of a zone
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherTransition {
+    pub previous: EorzeaWeather,
+    pub current: EorzeaWeather,
+}
+
+/// A set of acceptable weathers, and optionally a set of excluded ones
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WeatherFilter {
+    /// The acceptable weathers, an empty list accepts every weather
+    pub any_of: Vec<Weather>,
+    /// Weathers that never match, even if they are in `any_of`
+    pub excluding: Vec<Weather>,
+}
+
+impl WeatherFilter {
+    /// Creates a filter accepting any of the given weathers
+    pub fn any_of<I: IntoIterator<Item = Weather>>(weathers: I) -> WeatherFilter {
+        WeatherFilter {
+            any_of: weathers.into_iter().collect(),
+            excluding: Vec::new(),
+        }
+    }
+
+    /// Creates a filter accepting every weather except the given ones
+    pub fn none_of<I: IntoIterator<Item = Weather>>(weathers: I) -> WeatherFilter {
+        WeatherFilter::default().excluding(weathers)
+    }
+
+    /// Adds weathers to exclude from the filter
+    pub fn excluding<I: IntoIterator<Item = Weather>>(mut self, weathers: I) -> WeatherFilter {
+        self.excluding.extend(weathers);
+        self
+    }
+
+    /// Checks whether a weather is accepted by the filter
+    pub fn matches(&self, weather: Weather) -> bool {
+        (self.any_of.is_empty() || self.any_of.contains(&weather))
+            && !self.excluding.contains(&weather)
+    }
+}
+
+impl From<Weather> for WeatherFilter {
+    fn from(weather: Weather) -> Self {
+        WeatherFilter::any_of([weather])
+    }
+}
+
+/// How far a weather search looks before giving up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchHorizon {
+    /// Search this many intervals after (or before) the current one
+    Intervals(u32),
+    /// Search the intervals starting up to this long after (or ending up to this long before) the current time
+    Duration(Duration),
+}
+
+impl SearchHorizon {
+    // Number of intervals to search from the interval containing `current_epoch`, not counting that interval
+    pub(crate) fn interval_count(&self, current_epoch: i64, direction: ForecastDirection) -> usize {
+        match self {
+            SearchHorizon::Intervals(count) => *count as usize,
+            SearchHorizon::Duration(duration) => {
+                let seconds = duration.num_seconds().max(0);
+                let count = match direction {
+                    ForecastDirection::Forward => {
+                        weather_interval_index(current_epoch.saturating_add(seconds))
+                            - weather_interval_index(current_epoch)
+                    }
+                    ForecastDirection::Backward => {
+                        weather_interval_index(current_epoch)
+                            - weather_interval_index(current_epoch.saturating_sub(seconds))
+                    }
+                };
+                count as usize
+            }
+        }
+    }
+}
+
+/// Creates an iterator over the weather of a zone, using the bundled weather data
+/// See `Forecaster::forecast_iter`
+pub fn forecast_iter<Z: ZoneKey, T: ToUnixTimestamp>(
+    zone: Z,
+    current_time: T,
+    direction: ForecastDirection,
+) -> WeatherForecastIter<'static> {
+    WeatherDatabase::builtin()
+        .forecaster()
+        .forecast_iter(zone, current_time, direction)
+}
+
+/// The direction in time a forecast iterator moves in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForecastDirection {
+    Forward,
+    Backward,
+}
+
+/// An iterator over the consecutive weather intervals of a zone
+/// It ends at the first interval where the zone is not in the version of the weather data in effect. Created by `forecast_iter` or `Forecaster::forecast_iter`
+#[derive(Debug, Clone)]
+pub struct WeatherForecastIter<'a> {
+    forecaster: Forecaster<'a>,
+    zone_id: ZoneId,
+    next_interval_index: i64,
+    direction: ForecastDirection,
+}
+
+impl Iterator for WeatherForecastIter<'_> {
+    type Item = EorzeaWeather;
+
+    fn next(&mut self) -> Option<EorzeaWeather> {
+        let weather = self
+            .forecaster
+            .forecast_interval(self.zone_id, self.next_interval_index)?;
+        self.next_interval_index += match self.direction {
+            ForecastDirection::Forward => 1,
+            ForecastDirection::Backward => -1,
+        };
+        Some(weather)
+    }
+}
+
+/// Creates an iterator over the weather changes of several zones, using the bundled weather data
+/// See `Forecaster::weather_changes`
+pub fn weather_changes<Z: ZoneKey, T: ToUnixTimestamp>(
+    zones: &[Z],
+    start_time: T,
+    end_time: T,
+) -> WeatherChangeIter<'static> {
+    WeatherDatabase::builtin()
+        .forecaster()
+        .weather_changes(zones, start_time, end_time)
+}
+
+/// Finds the next time the weather of a zone changes, using the bundled weather data
+/// See `Forecaster::find_next_weather_change`
+pub fn find_next_weather_change<Z: ZoneKey, T: ToUnixTimestamp>(
+    zone: Z,
+    current_time: T,
+    horizon: SearchHorizon,
+) -> Option<WeatherChange> {
+    WeatherDatabase::builtin()
+        .forecaster()
+        .find_next_weather_change(zone, current_time, horizon)
+}
+
+/// A change of weather in a zone
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherChange {
+    pub zone_id: ZoneId,
+    pub zone_name: String,
+    pub previous: Weather,
+    pub current: Weather,
+    /// The time the new weather starts
+    pub time: i64,
+}
+
+impl WeatherChange {
+    /// Gets the time the new weather starts as a `DateTime<Utc>`
+    pub fn datetime(&self) -> DateTime<Utc> {
+        timestamp_to_datetime(self.time)
+    }
+
+    /// Gets the Eorzean date the new weather starts at
+    pub fn eorzean_date(&self) -> EorzeanDate {
+        convert_to_eorzean_date(self.time)
+    }
+}
+
+/// An iterator over the weather changes of several zones in chronological order
+/// Created by `weather_changes` or `Forecaster::weather_changes`
+#[derive(Debug, Clone)]
+pub struct WeatherChangeIter<'a> {
+    forecaster: Forecaster<'a>,
+    // Each zone with its weather in the last interval before `next_interval_index` that it was in the weather data
+    zones: Vec<(ZoneId, Option<Weather>)>,
+    next_interval_index: i64,
+    // The end of the range without the timing offset, comparable to the start of an interval
+    end_epoch: i64,
+    pending: VecDeque<WeatherChange>,
+}
+
+impl Iterator for WeatherChangeIter<'_> {
+    type Item = WeatherChange;
+
+    fn next(&mut self) -> Option<WeatherChange> {
+        // Intervals are aligned across zones, so merging the zones only needs one interval at a time
+        while self.pending.is_empty() {
+            let (start_time, _) = weather_interval_bounds(self.next_interval_index);
+            if self.zones.is_empty() || start_time >= self.end_epoch {
+                return None;
+            }
+            for (zone_id, previous) in self.zones.iter_mut() {
+                // Intervals where the zone is not in the weather data are skipped
+                let Some(forecast) = self
+                    .forecaster
+                    .forecast_interval(*zone_id, self.next_interval_index)
+                else {
+                    continue;
+                };
+                if let Some(previous) = previous.filter(|previous| *previous != forecast.weather) {
+                    self.pending.push_back(WeatherChange {
+                        zone_id: forecast.zone_id,
+                        zone_name: forecast.zone_name,
+                        previous,
+                        current: forecast.weather,
+                        time: forecast.start_time,
+                    });
+                }
+                *previous = Some(forecast.weather);
+            }
+            self.next_interval_index += 1;
+        }
+        self.pending.pop_front()
+    }
+}
+
+impl<'a> Forecaster<'a> {
+    /// Gets the weather for a given zone at a given time
+    ///
+    /// # Arguments
+    /// - `zone` - The name or `ZoneId` of the zone to calculate the forecast for
+    ///
+    /// # Returns
+    /// - A Weather struct representing the current weather
+    pub fn get_weather_by_time<Z: ZoneKey, T: ToUnixTimestamp>(
+        &self,
+        zone: Z,
+        current_time: T,
+    ) -> Weather {
+        let epoch = self.raw_time(current_time.to_unix_timestamp());
+        weather_in_zone(self.expect_zone_at(&zone, epoch), epoch)
+    }
+
+    /// Calculates the weather forecast for a given zone at a given time
+    /// Setting interval_offset to 0 will calculate the weather at the given time, as well as the start and end times of the interval
+    /// The forecast uses the version of the weather data in effect at the start of the forecast interval
+    ///
+    /// # Arguments
+    /// - `zone` - The name or `ZoneId` of the zone to calculate the forecast for
+    /// - `current_time` - The current time to calculate the forecast for
+    /// - `offset` - The intervals to calculate the forecast for. +1 means the next interval, -1 means the previous interval
+    ///
+    /// # Returns
+    /// - An EorzeaWeather struct representing the forecasted weather
+    ///
+    pub fn calculate_forecast<Z: ZoneKey, T: ToUnixTimestamp>(
+        &self,
+        zone: Z,
+        current_time: T,
+        interval_offset: i32,
+    ) -> EorzeaWeather {
+        // Each interval is 8 Eorzean hours. 00:00, 08:00, 16:00 are the start times
+        let current_epoch = self.raw_time(current_time.to_unix_timestamp());
+        let interval_index = weather_interval_index(current_epoch) + interval_offset as i64;
+        let (start_time, _) = weather_interval_bounds(interval_index);
+        let zone = self.expect_zone_at(&zone, start_time);
+        self.forecast_zone_interval(zone, interval_index)
+    }
+
+    /// Creates an iterator over the weather of a zone
+    /// The first item is the interval containing `current_time`, followed by the intervals after it (`Forward`)
+    /// or before it (`Backward`). The iterator only ends at an interval where the zone is not in the version of the
+    /// weather data in effect, limit it with adapters such as `take` or `take_while`.
+    ///
+    /// # Arguments
+    /// - `zone` - The name or `ZoneId` of the zone to calculate the forecast for
+    /// - `current_time` - The time to start iterating from
+    /// - `direction` - Whether to move forward or backward in time
+    ///
+    /// # Returns
+    /// - An iterator of EorzeaWeather structs for consecutive intervals
+    pub fn forecast_iter<Z: ZoneKey, T: ToUnixTimestamp>(
+        &self,
+        zone: Z,
+        current_time: T,
+        direction: ForecastDirection,
+    ) -> WeatherForecastIter<'a> {
+        let current_epoch = self.raw_time(current_time.to_unix_timestamp());
+        WeatherForecastIter {
+            forecaster: *self,
+            zone_id: self.expect_zone_at(&zone, current_epoch).id,
+            next_interval_index: weather_interval_index(current_epoch),
+            direction,
+        }
+    }
+
+    // Searches the intervals after (or before) the current one for a weather matching a filter
+    fn search_weather<Z: ZoneKey, T: ToUnixTimestamp>(
+        &self,
+        zone: Z,
+        current_time: T,
+        filter: &WeatherFilter,
+        horizon: SearchHorizon,
+        direction: ForecastDirection,
+    ) -> Option<EorzeaWeather> {
+        let current_epoch = current_time.to_unix_timestamp();
+        let forecasts = self.forecast_iter(zone, current_epoch, direction);
+        if !self.zone_can_match(forecasts.zone_id, filter) {
+            return None;
+        }
+        forecasts
+            .skip(1)
+            .take(horizon.interval_count(self.raw_time(current_epoch), direction))
+            .find(|forecast| filter.matches(forecast.weather))
+    }
+
+    /// Finds the next interval with a given weather
+    /// The search starts with the interval after the current one and returns early if the zone's rates do not include the weather
+    ///
+    /// # Arguments
+    /// - `zone` - The name or `ZoneId` of the zone to calculate the forecast for
+    /// - `current_time` - The current time to calculate the forecast for
+    /// - `target_weather` - The weather effect to search for
+    /// - `horizon` - How far ahead to search
+    ///
+    /// # Returns
+    /// - An EorzeaWeather struct representing the next weather effect, or `None` if it does not occur within the horizon
+    pub fn find_next_weather_occurrence<Z: ZoneKey, T: ToUnixTimestamp>(
+        &self,
+        zone: Z,
+        current_time: T,
+        target_weather: Weather,
+        horizon: SearchHorizon,
+    ) -> Option<EorzeaWeather> {
+        let filter = WeatherFilter::from(target_weather);
+        self.search_weather(
+            zone,
+            current_time,
+            &filter,
+            horizon,
+            ForecastDirection::Forward,
+        )
+    }
+
+    /// Finds the previous interval with a given weather
+    /// The search starts with the interval before the current one and returns early if the zone's rates do not include the weather
+    ///
+    /// # Arguments
+    /// - `zone` - The name or `ZoneId` of the zone to calculate the forecast for
+    /// - `current_time` - The current time to calculate the forecast for
+    /// - `target_weather` - The weather effect to search for
+    /// - `horizon` - How far back to search
+    ///
+    /// # Returns
+    /// - An EorzeaWeather struct representing the previous weather effect, or `None` if it did not occur within the horizon
+    pub fn find_previous_weather_occurrence<Z: ZoneKey, T: ToUnixTimestamp>(
+        &self,
+        zone: Z,
+        current_time: T,
+        target_weather: Weather,
+        horizon: SearchHorizon,
+    ) -> Option<EorzeaWeather> {
+        let filter = WeatherFilter::from(target_weather);
+        self.search_weather(
+            zone,
+            current_time,
+            &filter,
+            horizon,
+            ForecastDirection::Backward,
+        )
+    }
+
+    /// Finds the next interval whose weather matches a filter
+    /// The search starts with the interval after the current one and returns early if no weather in the zone's rates matches
+    ///
+    /// # Arguments
+    /// - `zone` - The name or `ZoneId` of the zone to calculate the forecast for
+    /// - `current_time` - The current time to calculate the forecast for
+    /// - `filter` - The acceptable and excluded weathers
+    /// - `horizon` - How far ahead to search
+    ///
+    /// # Returns
+    /// - An EorzeaWeather struct whose `weather` is the one that matched, or `None` if nothing matches within the horizon
+    pub fn find_next_matching_weather<Z: ZoneKey, T: ToUnixTimestamp>(
+        &self,
+        zone: Z,
+        current_time: T,
+        filter: &WeatherFilter,
+        horizon: SearchHorizon,
+    ) -> Option<EorzeaWeather> {
+        self.search_weather(
+            zone,
+            current_time,
+            filter,
+            horizon,
+            ForecastDirection::Forward,
+        )
+    }
+
+    /// Finds the previous interval whose weather matches a filter
+    /// The search starts with the interval before the current one and returns early if no weather in the zone's rates matches
+    ///
+    /// # Arguments
+    /// - `zone` - The name or `ZoneId` of the zone to calculate the forecast for
+    /// - `current_time` - The current time to calculate the forecast for
+    /// - `filter` - The acceptable and excluded weathers
+    /// - `horizon` - How far back to search
+    ///
+    /// # Returns
+    /// - An EorzeaWeather struct whose `weather` is the one that matched, or `None` if nothing matched within the horizon
+    pub fn find_previous_matching_weather<Z: ZoneKey, T: ToUnixTimestamp>(
+        &self,
+        zone: Z,
+        current_time: T,
+        filter: &WeatherFilter,
+        horizon: SearchHorizon,
+    ) -> Option<EorzeaWeather> {
+        self.search_weather(
+            zone,
+            current_time,
+            filter,
+            horizon,
+            ForecastDirection::Backward,
+        )
+    }
+
+    /// Finds the next intervals where the weather changes from one set of weathers to another (e.g. Fog followed by Clear Skies)
+    /// The search starts with the interval after the current one and returns early if the zone can never match either filter
+    ///
+    /// # Arguments
+    /// - `zone` - The name or `ZoneId` of the zone to calculate the forecast for
+    /// - `current_time` - The current time to calculate the forecast for
+    /// - `previous` - The acceptable weathers of the interval before the transition
+    /// - `current` - The acceptable weathers of the interval after the transition
+    /// - `count` - The maximum number of transitions to return
+    /// - `horizon` - How far ahead to search
+    ///
+    /// # Returns
+    /// - A Vec of up to `count` transitions in chronological order, each with both intervals
+    pub fn find_weather_transitions<Z: ZoneKey, T: ToUnixTimestamp>(
+        &self,
+        zone: Z,
+        current_time: T,
+        previous: &WeatherFilter,
+        current: &WeatherFilter,
+        count: usize,
+        horizon: SearchHorizon,
+    ) -> Vec<WeatherTransition> {
+        let current_epoch = current_time.to_unix_timestamp();
+        let mut forecasts = self.forecast_iter(zone, current_epoch, ForecastDirection::Forward);
+        if !self.zone_can_match(forecasts.zone_id, previous)
+            || !self.zone_can_match(forecasts.zone_id, current)
+        {
+            return Vec::new();
+        }
+        let Some(mut previous_forecast) = forecasts.next() else {
+            return Vec::new();
+        };
+        let mut transitions = Vec::new();
+        for forecast in forecasts
+            .take(horizon.interval_count(self.raw_time(current_epoch), ForecastDirection::Forward))
+        {
+            if transitions.len() >= count {
+                break;
+            }
+            if previous.matches(previous_forecast.weather) && current.matches(forecast.weather) {
+                transitions.push(WeatherTransition {
+                    previous: previous_forecast,
+                    current: forecast.clone(),
+                });
+            }
+            previous_forecast = forecast;
+        }
+        transitions
+    }
+
+    /// Finds the windows where a zone's weather matches a filter during a range of Eorzean time (e.g. Rain between ET 08:00 and 12:00)
+    /// The search starts with the current interval, so a window that is already open is included
+    ///
+    /// # Arguments
+    /// - `zone` - The name or `ZoneId` of the zone to calculate the forecast for
+    /// - `current_time` - The current time to calculate the forecast for
+    /// - `filter` - The acceptable weathers
+    /// - `time_range` - The Eorzean time of day the weather must occur in, which may wrap past midnight
+    /// - `count` - The maximum number of windows to return
+    /// - `horizon` - How far ahead to search
+    ///
+    /// # Returns
+    /// - A Vec of up to `count` windows in chronological order. A window never spans more than one weather interval,
+    ///   so a range covering consecutive matching intervals gives adjacent windows
+    pub fn find_weather_time_windows<Z: ZoneKey, T: ToUnixTimestamp>(
+        &self,
+        zone: Z,
+        current_time: T,
+        filter: &WeatherFilter,
+        time_range: &EorzeanTimeRange,
+        count: usize,
+        horizon: SearchHorizon,
+    ) -> Vec<WeatherWindow> {
+        let current_epoch = current_time.to_unix_timestamp();
+        let forecasts = self.forecast_iter(zone, current_epoch, ForecastDirection::Forward);
+        if !self.zone_can_match(forecasts.zone_id, filter) {
+            return Vec::new();
+        }
+        let interval_count =
+            horizon.interval_count(self.raw_time(current_epoch), ForecastDirection::Forward) + 1;
+        let mut windows = Vec::new();
+        for (forecast, interval_index) in forecasts
+            .take(interval_count)
+            .zip(weather_interval_index(self.raw_time(current_epoch))..)
+        {
+            if !filter.matches(forecast.weather) {
+                continue;
+            }
+            let (start_time, end_time) = weather_interval_bounds(interval_index);
+            for (window_start, window_end) in time_range.earth_windows(start_time, end_time) {
+                if self.adjust_time(window_end) <= current_epoch {
+                    continue;
+                }
+                if windows.len() >= count {
+                    return windows;
+                }
+                windows.push(WeatherWindow {
+                    start_time: self.adjust_time(window_start),
+                    end_time: self.adjust_time(window_end),
+                    forecast: forecast.clone(),
+                });
+            }
+        }
+        windows
+    }
+
+    /// Finds the times a zone's weather matches a filter within a span
+    /// The set can be combined with other sets, such as `EorzeanTimeRange::earth_window_set` or the weather of
+    /// another zone, see `TimeWindowSet`
+    ///
+    /// # Arguments
+    /// - `zone` - The name or `ZoneId` of the zone
+    /// - `filter` - The weathers to match
+    /// - `start_time` - The start of the span (inclusive)
+    /// - `end_time` - The end of the span (exclusive)
+    ///
+    /// # Returns
+    /// - The times of the matching intervals, with the timing offset applied and clipped to the span
+    pub fn weather_window_set<Z: ZoneKey, T: ToUnixTimestamp>(
+        &self,
+        zone: Z,
+        filter: &WeatherFilter,
+        start_time: T,
+        end_time: T,
+    ) -> TimeWindowSet {
+        let start_epoch = start_time.to_unix_timestamp();
+        let end_epoch = end_time.to_unix_timestamp();
+        if end_epoch <= start_epoch {
+            return TimeWindowSet::new();
+        }
+        let zone_id = self.expect_zone_at(&zone, self.raw_time(start_epoch)).id;
+        if !self.zone_can_match(zone_id, filter) {
+            return TimeWindowSet::new();
+        }
+        // Reported times are shifted by the offset, the interval after the last is included in case the precision
+        // moves its start into the span
+        let first_index = weather_interval_index(self.raw_time(start_epoch));
+        let last_index = weather_interval_index(self.raw_time(end_epoch - 1)) + 1;
+        let windows: TimeWindowSet = (first_index..=last_index)
+            .filter_map(|interval_index| self.forecast_interval(zone_id, interval_index))
+            .filter(|forecast| filter.matches(forecast.weather))
+            .map(|forecast| (forecast.start_time, forecast.end_time))
+            .collect();
+        windows.clip(start_epoch, end_epoch)
+    }
+
+    // Calls `visit` with each run of matching intervals after the current one, until it returns false
+    // Runs are cut off at the end of the horizon
+    fn scan_weather_runs<Z: ZoneKey, T: ToUnixTimestamp, F: FnMut(WeatherRun) -> bool>(
+        &self,
+        zone: Z,
+        current_time: T,
+        filter: &WeatherFilter,
+        horizon: SearchHorizon,
+        mut visit: F,
+    ) {
+        let current_epoch = current_time.to_unix_timestamp();
+        let forecasts = self.forecast_iter(zone, current_epoch, ForecastDirection::Forward);
+        if !self.zone_can_match(forecasts.zone_id, filter) {
+            return;
+        }
+        let mut run = Vec::new();
+        for forecast in forecasts
+            .skip(1)
+            .take(horizon.interval_count(self.raw_time(current_epoch), ForecastDirection::Forward))
+        {
+            if filter.matches(forecast.weather) {
+                run.push(forecast);
+            } else if !run.is_empty()
+                && !visit(WeatherRun::from_forecasts(std::mem::take(&mut run)))
+            {
+                return;
+            }
+        }
+        if !run.is_empty() {
+            visit(WeatherRun::from_forecasts(run));
+        }
+    }
+
+    /// Finds the next run of at least `min_length` consecutive intervals whose weather matches a filter
+    /// (e.g. Rain or Showers for three intervals in a row)
+    /// The search starts with the interval after the current one, so a run that is already under way only counts
+    /// its remaining intervals. Returns early if no weather in the zone's rates matches.
+    ///
+    /// # Arguments
+    /// - `zone` - The name or `ZoneId` of the zone to calculate the forecast for
+    /// - `current_time` - The current time to calculate the forecast for
+    /// - `filter` - The acceptable weathers
+    /// - `min_length` - The minimum number of consecutive intervals
+    /// - `horizon` - How far ahead to search, runs reaching past it are cut off at its end
+    ///
+    /// # Returns
+    /// - The whole run, which may be longer than `min_length`, or `None` if no run is long enough within the horizon
+    pub fn find_next_weather_run<Z: ZoneKey, T: ToUnixTimestamp>(
+        &self,
+        zone: Z,
+        current_time: T,
+        filter: &WeatherFilter,
+        min_length: usize,
+        horizon: SearchHorizon,
+    ) -> Option<WeatherRun> {
+        let mut found = None;
+        self.scan_weather_runs(zone, current_time, filter, horizon, |run| {
+            if run.length() >= min_length {
+                found = Some(run);
+                return false;
+            }
+            true
+        });
+        found
+    }
+
+    /// Finds the longest run of consecutive intervals whose weather matches a filter
+    /// The search starts with the interval after the current one and returns early if no weather in the zone's rates matches
+    ///
+    /// # Arguments
+    /// - `zone` - The name or `ZoneId` of the zone to calculate the forecast for
+    /// - `current_time` - The current time to calculate the forecast for
+    /// - `filter` - The acceptable weathers
+    /// - `horizon` - How far ahead to search, runs reaching past it are cut off at its end
+    ///
+    /// # Returns
+    /// - The longest run, the earliest one if several are equally long, or `None` if nothing matches within the horizon
+    pub fn find_longest_weather_run<Z: ZoneKey, T: ToUnixTimestamp>(
+        &self,
+        zone: Z,
+        current_time: T,
+        filter: &WeatherFilter,
+        horizon: SearchHorizon,
+    ) -> Option<WeatherRun> {
+        let mut longest: Option<WeatherRun> = None;
+        self.scan_weather_runs(zone, current_time, filter, horizon, |run| {
+            if longest
+                .as_ref()
+                .is_none_or(|longest| run.length() > longest.length())
+            {
+                longest = Some(run);
+            }
+            true
+        });
+        longest
+    }
+
+    // Builds a snapshot of the given zones, every zone shares the forecast target and weather table of each interval
+    // Zones missing from the weather table of any interval in the snapshot are left out
+    fn snapshot_zones(
+        &self,
+        zone_ids: Vec<ZoneId>,
+        current_epoch: i64,
+        upcoming_count: usize,
+    ) -> WeatherSnapshot {
+        let current_index = weather_interval_index(self.raw_time(current_epoch));
+        // The intervals either side of the snapshot are only needed for the neighbouring weather
+        let margin = usize::from(self.neighbouring_weather());
+        let intervals: Vec<(i64, i64, i32, &WeatherTable)> = (current_index - 1 - margin as i64
+            ..=current_index + (upcoming_count + margin) as i64)
+            .map(|interval_index| {
+                let (start_time, end_time) = weather_interval_bounds(interval_index);
+                (
+                    start_time,
+                    end_time,
+                    calculate_weather_forecast_target(start_time),
+                    self.database().table_at(start_time),
+                )
+            })
+            .collect();
+        let zones = zone_ids
+            .into_iter()
+            .filter_map(|zone_id| {
+                // The zone and its weather in each interval, calculated once and shared with the neighbouring intervals
+                let weathers: Vec<Option<(&ZoneWeather, Weather)>> = intervals
+                    .iter()
+                    .map(|(_, _, forecast_target, table)| {
+                        let zone = table.zone_by_id(zone_id)?;
+                        let weather = zone
+                            .weather_for_target(*forecast_target)
+                            .expect("No weather found for the forecast target");
+                        Some((zone, weather))
+                    })
+                    .collect();
+                let weather_at = |position: usize| weathers[position].map(|(_, weather)| weather);
+                let mut forecasts = (margin..intervals.len() - margin)
+                    .map(|position| {
+                        let (zone, weather) = weathers[position]?;
+                        let (start_time, end_time, _, _) = intervals[position];
+                        let (previous_weather, next_weather) = if self.neighbouring_weather() {
+                            (weather_at(position - 1), weather_at(position + 1))
+                        } else {
+                            (None, None)
+                        };
+                        Some(EorzeaWeather {
+                            start_time: self.adjust_time(start_time),
+                            end_time: self.adjust_time(end_time),
+                            zone_id,
+                            zone_name: zone.name.clone(),
+                            weather,
+                            previous_weather,
+                            next_weather,
+                        })
+                    })
+                    .collect::<Option<Vec<EorzeaWeather>>>()?;
+                let upcoming = forecasts.split_off(2);
+                let current = forecasts.pop()?;
+                let previous = forecasts.pop()?;
+                Some(ZoneWeatherSnapshot {
+                    zone_id,
+                    zone_name: current.zone_name.clone(),
+                    previous,
+                    current,
+                    upcoming,
+                })
+            })
+            .collect();
+        WeatherSnapshot {
+            time: current_epoch,
+            zones,
+        }
+    }
+
+    /// Gets the previous, current and upcoming weather of every zone at once
+    /// The forecast target of each interval is calculated once and shared by all zones, which is much cheaper than
+    /// calling `calculate_forecast` per zone
+    ///
+    /// # Arguments
+    /// - `current_time` - The time to take the snapshot for
+    /// - `upcoming_count` - The number of intervals after the current one to include
+    ///
+    /// # Returns
+    /// - A WeatherSnapshot with the zones of the weather data in effect at `current_time`, in table order.
+    ///   A zone that is missing from the weather data of one of the intervals is left out.
+    pub fn weather_snapshot<T: ToUnixTimestamp>(
+        &self,
+        current_time: T,
+        upcoming_count: usize,
+    ) -> WeatherSnapshot {
+        let current_epoch = current_time.to_unix_timestamp();
+        let zone_ids = self
+            .database()
+            .table_at(self.raw_time(current_epoch))
+            .zones()
+            .iter()
+            .map(|zone| zone.id)
+            .collect();
+        self.snapshot_zones(zone_ids, current_epoch, upcoming_count)
+    }
+
+    /// Gets the previous, current and upcoming weather of a set of zones at once
+    /// See `Forecaster::weather_snapshot`
+    ///
+    /// # Arguments
+    /// - `zones` - The names or `ZoneId`s of the zones to include
+    /// - `current_time` - The time to take the snapshot for
+    /// - `upcoming_count` - The number of intervals after the current one to include
+    ///
+    /// # Returns
+    /// - A WeatherSnapshot with the zones in the order they were given
+    pub fn weather_snapshot_for<Z: ZoneKey, T: ToUnixTimestamp>(
+        &self,
+        zones: &[Z],
+        current_time: T,
+        upcoming_count: usize,
+    ) -> WeatherSnapshot {
+        let current_epoch = current_time.to_unix_timestamp();
+        let zone_ids = zones
+            .iter()
+            .map(|zone| self.expect_zone_at(zone, self.raw_time(current_epoch)).id)
+            .collect();
+        self.snapshot_zones(zone_ids, current_epoch, upcoming_count)
+    }
+
+    /// Creates an iterator over the weather changes of several zones, merged into one chronological sequence
+    /// Changes are calculated one interval at a time as the iterator advances. Changes at the same time are
+    /// ordered like `zones`, and an interval with the same weather as the one before it is not a change.
+    ///
+    /// # Arguments
+    /// - `zones` - The names or `ZoneId`s of the zones to follow
+    /// - `start_time` - The earliest time of a change (inclusive)
+    /// - `end_time` - The latest time of a change (exclusive)
+    ///
+    /// # Returns
+    /// - An iterator of WeatherChange structs
+    pub fn weather_changes<Z: ZoneKey, T: ToUnixTimestamp>(
+        &self,
+        zones: &[Z],
+        start_time: T,
+        end_time: T,
+    ) -> WeatherChangeIter<'a> {
+        let start_epoch = start_time.to_unix_timestamp();
+        // Reported times are shifted by the offset, so the range is converted back before finding the first interval
+        // starting at or after the start time
+        let first_interval_index = weather_interval_index(self.raw_time(start_epoch) - 1) + 1;
+        let zones = zones
+            .iter()
+            .map(|zone| {
+                let zone_id = self.expect_zone_at(zone, self.raw_time(start_epoch)).id;
+                (
+                    zone_id,
+                    self.forecast_interval(zone_id, first_interval_index - 1)
+                        .map(|forecast| forecast.weather),
+                )
+            })
+            .collect();
+        WeatherChangeIter {
+            forecaster: *self,
+            zones,
+            next_interval_index: first_interval_index,
+            end_epoch: self.raw_time(end_time.to_unix_timestamp()),
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Finds the next time the weather of a zone changes
+    /// Consecutive intervals with the same weather are not a change, so the change may be several intervals away
+    ///
+    /// # Arguments
+    /// - `zone` - The name or `ZoneId` of the zone
+    /// - `current_time` - The current time, a change at exactly this time is not included
+    /// - `horizon` - How far ahead to search
+    ///
+    /// # Returns
+    /// - The next change, or `None` if the weather does not change within the horizon
+    pub fn find_next_weather_change<Z: ZoneKey, T: ToUnixTimestamp>(
+        &self,
+        zone: Z,
+        current_time: T,
+        horizon: SearchHorizon,
+    ) -> Option<WeatherChange> {
+        let current_epoch = current_time.to_unix_timestamp();
+        // The horizon counts intervals from the one containing the current time once the offset is taken off
+        let last_interval_index = weather_interval_index(self.raw_time(current_epoch))
+            + horizon.interval_count(self.raw_time(current_epoch), ForecastDirection::Forward)
+                as i64;
+        let (_, end_time) = weather_interval_bounds(last_interval_index);
+        self.weather_changes(&[zone], current_epoch + 1, end_time + self.timing_offset())
+            .next()
+    }
+}
+
+impl WeatherDatabase {
+    /// Gets the chance of each weather in a zone, using the latest version of the weather data
+    /// See `ZoneWeather::weather_chances`
+    ///
+    /// # Arguments
+    /// - `zone` - The name or `ZoneId` of the zone
+    ///
+    /// # Returns
+    /// - A Vec of weathers and their chance in percent, with weathers listed in several rows of the rates combined
+    pub fn weather_chances<Z: ZoneKey>(&self, zone: Z) -> Vec<(Weather, i32)> {
+        zone.find_zone(self.table())
+            .unwrap_or_else(|| panic!("Unable to find the zone '{}' in the weather data", zone))
+            .weather_chances()
+    }
+
+    /// Finds the zones where a weather can occur, using the latest version of the weather data
+    /// See `WeatherTable::zones_with_weather`
+    ///
+    /// # Returns
+    /// - A Vec of zones and the chance of the weather in percent, the most likely zone first
+    pub fn zones_with_weather(&self, weather: Weather) -> Vec<(&ZoneWeather, i32)> {
+        self.table().zones_with_weather(weather)
+    }
 }
@@ -0,0 +1,187 @@
+#[allow(deprecated)]
+use crate::eorzean_weather::get_global_weather_timing_offset;
+use crate::eorzean_weather::{
+    weather_in_zone, weather_interval_bounds, EorzeaWeather, Weather, WeatherFilter,
+};
+use crate::weather_data::{WeatherDatabase, ZoneId, ZoneKey, ZoneWeather};
+
+/// How precisely the start and end times of forecasts are reported
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimePrecision {
+    /// Exact Earth seconds
+    #[default]
+    Second,
+    /// Rounded down to the Earth minute
+    Minute,
+}
+
+impl TimePrecision {
+    fn seconds(&self) -> i64 {
+        match self {
+            TimePrecision::Second => 1,
+            TimePrecision::Minute => 60,
+        }
+    }
+}
+
+/// Forecasts the weather of a weather data set with its own settings
+/// A Forecaster is cheap to copy, so each request or task can hold one with its own timing offset
+/// instead of sharing the global weather timing offset
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Forecaster<'a> {
+    database: &'a WeatherDatabase,
+    timing_offset: i64,
+    precision: TimePrecision,
+    neighbouring_weather: bool,
+}
+
+impl<'a> Forecaster<'a> {
+    /// Creates a forecaster for a weather data set, with no timing offset and exact times
+    pub fn new(database: &'a WeatherDatabase) -> Forecaster<'a> {
+        Forecaster {
+            database,
+            timing_offset: 0,
+            precision: TimePrecision::Second,
+            neighbouring_weather: false,
+        }
+    }
+
+    /// Sets the offset in seconds added to the start and end times of forecasts
+    pub fn with_timing_offset(mut self, timing_offset: i64) -> Forecaster<'a> {
+        self.timing_offset = timing_offset;
+        self
+    }
+
+    /// Sets how precisely the start and end times of forecasts are reported
+    pub fn with_precision(mut self, precision: TimePrecision) -> Forecaster<'a> {
+        self.precision = precision;
+        self
+    }
+
+    /// Sets whether forecasts include the weather of the intervals before and after them
+    /// See `EorzeaWeather::previous_weather` and `EorzeaWeather::next_weather`
+    pub fn with_neighbouring_weather(mut self, neighbouring_weather: bool) -> Forecaster<'a> {
+        self.neighbouring_weather = neighbouring_weather;
+        self
+    }
+
+    /// Sets the weather data set to forecast with
+    pub fn with_database(mut self, database: &'a WeatherDatabase) -> Forecaster<'a> {
+        self.database = database;
+        self
+    }
+
+    /// Gets the weather data set the forecaster uses
+    pub fn database(&self) -> &'a WeatherDatabase {
+        self.database
+    }
+
+    /// Gets the offset in seconds added to the start and end times of forecasts
+    pub fn timing_offset(&self) -> i64 {
+        self.timing_offset
+    }
+
+    /// Gets how precisely the start and end times of forecasts are reported
+    pub fn precision(&self) -> TimePrecision {
+        self.precision
+    }
+
+    /// Gets whether forecasts include the weather of the intervals before and after them
+    pub fn neighbouring_weather(&self) -> bool {
+        self.neighbouring_weather
+    }
+
+    // Converts a time calculated from the forecast into a reported time, applying the offset and precision
+    pub(crate) fn adjust_time(&self, time: i64) -> i64 {
+        let time = time + self.timing_offset;
+        time - time.rem_euclid(self.precision.seconds())
+    }
+
+    // Converts a reported time back into the time forecasts are calculated from, removing the offset
+    // Every method picks its intervals and weather data version from the raw time, so a reported time inside an
+    // interval always falls within the reported bounds of that interval
+    pub(crate) fn raw_time(&self, time: i64) -> i64 {
+        time - self.timing_offset
+    }
+
+    // Finds a zone in the version of the weather data in effect at a given time, panicking if it does not exist
+    pub(crate) fn expect_zone_at<Z: ZoneKey>(&self, zone: &Z, epoch: i64) -> &'a ZoneWeather {
+        zone.find_zone(self.database.table_at(epoch))
+            .unwrap_or_else(|| panic!("Unable to find the zone '{}' in the weather data", zone))
+    }
+
+    // Calculates the weather of a zone for a weather interval
+    // Returns `None` if the zone is not in the version of the weather data in effect at the start of the interval
+    pub(crate) fn forecast_interval(
+        &self,
+        zone_id: ZoneId,
+        interval_index: i64,
+    ) -> Option<EorzeaWeather> {
+        let (start_time, _) = weather_interval_bounds(interval_index);
+        let zone = self.database.table_at(start_time).zone_by_id(zone_id)?;
+        Some(self.forecast_zone_interval(zone, interval_index))
+    }
+
+    // Calculates the weather of a zone for a weather interval, the zone must be from the version of the weather data
+    // in effect at the start of the interval
+    pub(crate) fn forecast_zone_interval(
+        &self,
+        zone: &ZoneWeather,
+        interval_index: i64,
+    ) -> EorzeaWeather {
+        let (start_time, end_time) = weather_interval_bounds(interval_index);
+        let (previous_weather, next_weather) =
+            self.neighbouring_weather_of(zone.id, interval_index);
+        EorzeaWeather {
+            start_time: self.adjust_time(start_time),
+            end_time: self.adjust_time(end_time),
+            zone_id: zone.id,
+            zone_name: zone.name.clone(),
+            weather: weather_in_zone(zone, start_time),
+            previous_weather,
+            next_weather,
+        }
+    }
+
+    // Gets the weather of a zone in the intervals before and after a weather interval, if the forecaster includes
+    // them and the zone is in the weather data in effect for each interval
+    pub(crate) fn neighbouring_weather_of(
+        &self,
+        zone_id: ZoneId,
+        interval_index: i64,
+    ) -> (Option<Weather>, Option<Weather>) {
+        if !self.neighbouring_weather {
+            return (None, None);
+        }
+        let weather_at = |interval_index: i64| {
+            let (start_time, _) = weather_interval_bounds(interval_index);
+            let zone = self.database.table_at(start_time).zone_by_id(zone_id)?;
+            Some(weather_in_zone(zone, start_time))
+        };
+        (
+            weather_at(interval_index - 1),
+            weather_at(interval_index + 1),
+        )
+    }
+
+    // Checks whether any weather in the rates of a zone matches a filter, in any version of the weather data
+    pub(crate) fn zone_can_match(&self, zone_id: ZoneId, filter: &WeatherFilter) -> bool {
+        self.database.versions().iter().any(|version| {
+            version.table.zone_by_id(zone_id).is_some_and(|zone| {
+                zone.rates
+                    .iter()
+                    .any(|(weather, _)| filter.matches(*weather))
+            })
+        })
+    }
+}
+
+impl WeatherDatabase {
+    /// Creates a forecaster for the weather data
+    /// The forecaster uses the deprecated global weather timing offset, use `Forecaster::new` to start without it
+    pub fn forecaster(&self) -> Forecaster<'_> {
+        #[allow(deprecated)]
+        let timing_offset = get_global_weather_timing_offset();
+        Forecaster::new(self).with_timing_offset(timing_offset)
+    }
+}
@@ -1,5 +1,24 @@
+pub mod calibration;
+pub mod clock;
+pub mod datamine_import;
 pub mod eorzean_time;
 pub mod eorzean_weather;
+pub mod forecaster;
+pub mod time_window_set;
+pub mod weather_analysis;
+pub mod weather_data;
+pub mod weather_query;
+pub mod weather_solver;
+pub mod zone_resolver;
 
+pub use calibration::*;
+pub use clock::*;
 pub use eorzean_time::*;
 pub use eorzean_weather::*;
+pub use forecaster::*;
+pub use time_window_set::*;
+pub use weather_analysis::*;
+pub use weather_data::*;
+pub use weather_query::*;
+pub use weather_solver::*;
+pub use zone_resolver::*;
@@ -0,0 +1,232 @@
+use crate::eorzean_time::ToUnixTimestamp;
+
+use chrono::Duration;
+
+/// A set of Earth time windows, such as the times a zone has a weather
+/// Windows are (start, end) Unix timestamps with the start inclusive and the end exclusive. The set keeps them
+/// sorted, drops empty windows and joins windows that overlap or touch, so two sets covering the same times are equal.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct TimeWindowSet {
+    windows: Vec<(i64, i64)>,
+}
+
+impl TimeWindowSet {
+    /// Creates an empty set
+    pub fn new() -> TimeWindowSet {
+        TimeWindowSet::default()
+    }
+
+    /// Creates a set containing a single window
+    ///
+    /// # Arguments
+    /// - `start_time` - The start of the window (inclusive)
+    /// - `end_time` - The end of the window (exclusive)
+    ///
+    /// # Returns
+    /// - A set with the window, or an empty set if the end is not after the start
+    pub fn from_window<T: ToUnixTimestamp>(start_time: T, end_time: T) -> TimeWindowSet {
+        let mut set = TimeWindowSet::new();
+        set.insert(start_time, end_time);
+        set
+    }
+
+    // Builds a set from windows in any order, sorting and joining them
+    fn from_unsorted(mut windows: Vec<(i64, i64)>) -> TimeWindowSet {
+        windows.retain(|(start, end)| start < end);
+        windows.sort_unstable();
+        let mut joined: Vec<(i64, i64)> = Vec::with_capacity(windows.len());
+        for window in windows {
+            match joined.last_mut() {
+                Some(last) if window.0 <= last.1 => last.1 = last.1.max(window.1),
+                _ => joined.push(window),
+            }
+        }
+        TimeWindowSet { windows: joined }
+    }
+
+    /// Adds a window to the set, joining it with any windows it overlaps or touches
+    /// Windows whose end is not after their start are ignored
+    pub fn insert<T: ToUnixTimestamp>(&mut self, start_time: T, end_time: T) {
+        let (start, end) = (start_time.to_unix_timestamp(), end_time.to_unix_timestamp());
+        if start >= end {
+            return;
+        }
+        // Windows before `first` end before the new window starts, windows from `last` start after it ends
+        let first = self
+            .windows
+            .partition_point(|(_, window_end)| *window_end < start);
+        let last = self
+            .windows
+            .partition_point(|(window_start, _)| *window_start <= end);
+        let joined = self.windows[first..last]
+            .iter()
+            .fold((start, end), |(start, end), window| {
+                (start.min(window.0), end.max(window.1))
+            });
+        self.windows.splice(first..last, [joined]);
+    }
+
+    /// Gets the windows of the set in chronological order
+    pub fn windows(&self) -> &[(i64, i64)] {
+        &self.windows
+    }
+
+    /// Creates an iterator over the windows of the set in chronological order
+    pub fn iter(&self) -> std::slice::Iter<'_, (i64, i64)> {
+        self.windows.iter()
+    }
+
+    /// Checks whether the set has no windows
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    /// Gets the number of separate windows in the set
+    pub fn len(&self) -> usize {
+        self.windows.len()
+    }
+
+    /// Gets the start of the first window and the end of the last, or `None` if the set is empty
+    pub fn bounds(&self) -> Option<(i64, i64)> {
+        Some((self.windows.first()?.0, self.windows.last()?.1))
+    }
+
+    /// Gets the total length of the windows
+    pub fn total_duration(&self) -> Duration {
+        Duration::seconds(self.windows.iter().map(|(start, end)| end - start).sum())
+    }
+
+    /// Checks whether an instant is within any window of the set
+    pub fn contains<T: ToUnixTimestamp>(&self, time: T) -> bool {
+        self.window_containing(time).is_some()
+    }
+
+    /// Finds the window containing an instant
+    ///
+    /// # Returns
+    /// - The window, or `None` if the instant is not in the set
+    pub fn window_containing<T: ToUnixTimestamp>(&self, time: T) -> Option<(i64, i64)> {
+        let time = time.to_unix_timestamp();
+        self.next_window(time).filter(|(start, _)| *start <= time)
+    }
+
+    /// Finds the first window containing an instant or starting after it
+    ///
+    /// # Returns
+    /// - The window, or `None` if every window of the set ends at or before the instant
+    pub fn next_window<T: ToUnixTimestamp>(&self, time: T) -> Option<(i64, i64)> {
+        let time = time.to_unix_timestamp();
+        let index = self.windows.partition_point(|(_, end)| *end <= time);
+        self.windows.get(index).copied()
+    }
+
+    /// Calculates the times in either set
+    pub fn union(&self, other: &TimeWindowSet) -> TimeWindowSet {
+        TimeWindowSet::from_unsorted(self.windows.iter().chain(&other.windows).copied().collect())
+    }
+
+    /// Calculates the times in both sets
+    pub fn intersection(&self, other: &TimeWindowSet) -> TimeWindowSet {
+        let mut windows = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.windows.get(i), other.windows.get(j)) {
+            let window = (a.0.max(b.0), a.1.min(b.1));
+            if window.0 < window.1 {
+                windows.push(window);
+            }
+            // Move past whichever window ends first, it cannot overlap any later window of the other set
+            if a.1 < b.1 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        TimeWindowSet { windows }
+    }
+
+    /// Calculates the times in this set that are not in the other
+    pub fn difference(&self, other: &TimeWindowSet) -> TimeWindowSet {
+        match self.bounds() {
+            Some((start, end)) => self.intersection(&other.complement(start, end)),
+            None => TimeWindowSet::new(),
+        }
+    }
+
+    /// Calculates the times within a span that are not in the set
+    ///
+    /// # Arguments
+    /// - `start_time` - The start of the span (inclusive)
+    /// - `end_time` - The end of the span (exclusive)
+    ///
+    /// # Returns
+    /// - The gaps between the windows of the set, clipped to the span
+    pub fn complement<T: ToUnixTimestamp>(&self, start_time: T, end_time: T) -> TimeWindowSet {
+        let (start, end) = (start_time.to_unix_timestamp(), end_time.to_unix_timestamp());
+        let mut windows = Vec::new();
+        let mut gap_start = start;
+        for (window_start, window_end) in self.clip(start, end).windows {
+            if gap_start < window_start {
+                windows.push((gap_start, window_start));
+            }
+            gap_start = window_end;
+        }
+        if gap_start < end {
+            windows.push((gap_start, end));
+        }
+        TimeWindowSet { windows }
+    }
+
+    /// Calculates the times of the set within a span
+    ///
+    /// # Arguments
+    /// - `start_time` - The start of the span (inclusive)
+    /// - `end_time` - The end of the span (exclusive)
+    pub fn clip<T: ToUnixTimestamp>(&self, start_time: T, end_time: T) -> TimeWindowSet {
+        self.intersection(&TimeWindowSet::from_window(start_time, end_time))
+    }
+
+    /// Joins windows separated by gaps no longer than `max_gap`, e.g. to treat a short break in a weather as one window
+    pub fn merge_gaps(&self, max_gap: Duration) -> TimeWindowSet {
+        let max_gap = max_gap.num_seconds();
+        let mut windows: Vec<(i64, i64)> = Vec::with_capacity(self.windows.len());
+        for window in &self.windows {
+            match windows.last_mut() {
+                Some(last) if window.0 - last.1 <= max_gap => last.1 = window.1,
+                _ => windows.push(*window),
+            }
+        }
+        TimeWindowSet { windows }
+    }
+}
+
+impl FromIterator<(i64, i64)> for TimeWindowSet {
+    fn from_iter<I: IntoIterator<Item = (i64, i64)>>(windows: I) -> TimeWindowSet {
+        TimeWindowSet::from_unsorted(windows.into_iter().collect())
+    }
+}
+
+impl Extend<(i64, i64)> for TimeWindowSet {
+    fn extend<I: IntoIterator<Item = (i64, i64)>>(&mut self, windows: I) {
+        for (start, end) in windows {
+            self.insert(start, end);
+        }
+    }
+}
+
+impl IntoIterator for TimeWindowSet {
+    type Item = (i64, i64);
+    type IntoIter = std::vec::IntoIter<(i64, i64)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.windows.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a TimeWindowSet {
+    type Item = &'a (i64, i64);
+    type IntoIter = std::slice::Iter<'a, (i64, i64)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.windows.iter()
+    }
+}
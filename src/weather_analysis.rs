@@ -0,0 +1,288 @@
+use crate::eorzean_time::{EorzeanTimeRange, ToUnixTimestamp};
+use crate::eorzean_weather::{
+    weather_interval_bounds, weather_interval_index, ForecastDirection, SearchHorizon,
+    WeatherFilter, EARTH_SECONDS_PER_WEATHER_INTERVAL,
+};
+use crate::forecaster::Forecaster;
+use crate::time_window_set::TimeWindowSet;
+use crate::weather_data::{WeatherDatabase, ZoneId, ZoneKey, ZoneWeather};
+
+use chrono::Duration;
+
+/// Weather conditions to wait for
+#[derive(Debug, Clone, PartialEq)]
+pub enum WeatherTarget {
+    /// An interval whose weather matches the filter
+    Weather(WeatherFilter),
+    /// An interval whose weather matches `current`, following one that matches `previous`
+    Transition {
+        previous: WeatherFilter,
+        current: WeatherFilter,
+    },
+    /// A matching weather during a range of Eorzean time
+    TimeWindow {
+        filter: WeatherFilter,
+        time_range: EorzeanTimeRange,
+    },
+}
+
+/// How long a weather target takes to come around, in theory and as observed in the forecast
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaitStatistics {
+    /// The theoretical chance that a weather interval contains the target, from the weather rates
+    pub probability: f64,
+    /// The number of weather intervals scanned
+    pub intervals_scanned: usize,
+    /// The fraction of scanned intervals that contained the target
+    pub observed_probability: f64,
+    /// The number of separate occurrences, an occurrence spanning consecutive intervals counts once
+    pub occurrences: usize,
+    /// The waits from the start of each scanned interval until the target is next in effect
+    /// Intervals with no occurrence after them within the scan are left out
+    pub mean_wait: Option<Duration>,
+    pub median_wait: Option<Duration>,
+    pub p95_wait: Option<Duration>,
+    /// The longest stretch of the scan without the target, including the stretches at either end
+    pub longest_drought: Duration,
+}
+
+impl WaitStatistics {
+    /// Calculates the theoretical expected wait from the start of an interval, treating intervals as independent
+    ///
+    /// # Returns
+    /// - The expected wait, or `None` if the target is impossible
+    pub fn expected_wait(&self) -> Option<Duration> {
+        if self.probability <= 0.0 {
+            return None;
+        }
+        let intervals = (1.0 - self.probability) / self.probability;
+        Some(seconds_to_duration(
+            intervals * EARTH_SECONDS_PER_WEATHER_INTERVAL as f64,
+        ))
+    }
+}
+
+fn seconds_to_duration(seconds: f64) -> Duration {
+    Duration::milliseconds((seconds * 1000.0).round() as i64)
+}
+
+// Gets the chance in percent that a zone has a weather matching a filter
+fn filter_chance(zone: &ZoneWeather, filter: &WeatherFilter) -> f64 {
+    zone.weather_chances()
+        .into_iter()
+        .filter(|(weather, _)| filter.matches(*weather))
+        .map(|(_, chance)| chance as f64)
+        .sum::<f64>()
+        / 100.0
+}
+
+// Gets the fraction of the three weather intervals of an Eorzean day that overlap a range of Eorzean time
+fn time_range_interval_fraction(time_range: &EorzeanTimeRange) -> f64 {
+    let overlapping = (0..3)
+        .filter(|interval_index| {
+            let (start_time, end_time) = weather_interval_bounds(*interval_index);
+            !time_range.earth_windows(start_time, end_time).is_empty()
+        })
+        .count();
+    overlapping as f64 / 3.0
+}
+
+/// Calculates the theoretical chance of a weather target, using the bundled weather data
+/// See `WeatherDatabase::weather_probability`
+pub fn weather_probability<Z: ZoneKey, T: ToUnixTimestamp>(
+    zone: Z,
+    target: &WeatherTarget,
+    time: T,
+) -> f64 {
+    WeatherDatabase::builtin().weather_probability(zone, target, time)
+}
+
+/// Scans the forecast for the waits until a weather target, using the bundled weather data
+/// See `Forecaster::analyze_weather_wait`
+pub fn analyze_weather_wait<Z: ZoneKey, T: ToUnixTimestamp>(
+    zone: Z,
+    target: &WeatherTarget,
+    start_time: T,
+    horizon: SearchHorizon,
+) -> WaitStatistics {
+    WeatherDatabase::builtin()
+        .forecaster()
+        .analyze_weather_wait(zone, target, start_time, horizon)
+}
+
+// The result of scanning the forecast for a weather target
+struct TargetScan {
+    // The Earth time spans where the target is in effect
+    spans: TimeWindowSet,
+    // The start times of the scanned intervals
+    interval_starts: Vec<i64>,
+    // The number of scanned intervals containing the target
+    matching_intervals: usize,
+}
+
+impl WeatherDatabase {
+    /// Calculates the theoretical chance that a weather interval contains a weather target,
+    /// using the version of the weather data in effect at a given time
+    /// Transitions treat the forecast targets of consecutive intervals as independent, and time windows count an
+    /// interval if any part of it falls within the range of Eorzean time
+    ///
+    /// # Arguments
+    /// - `zone` - The name or `ZoneId` of the zone
+    /// - `target` - The weather conditions
+    /// - `time` - The time whose version of the weather data to use
+    ///
+    /// # Returns
+    /// - A chance from 0.0 to 1.0
+    pub fn weather_probability<Z: ZoneKey, T: ToUnixTimestamp>(
+        &self,
+        zone: Z,
+        target: &WeatherTarget,
+        time: T,
+    ) -> f64 {
+        let zone = zone
+            .find_zone(self.table_at(time))
+            .unwrap_or_else(|| panic!("Unable to find the zone '{}' in the weather data", zone));
+        match target {
+            WeatherTarget::Weather(filter) => filter_chance(zone, filter),
+            WeatherTarget::Transition { previous, current } => {
+                filter_chance(zone, previous) * filter_chance(zone, current)
+            }
+            WeatherTarget::TimeWindow { filter, time_range } => {
+                filter_chance(zone, filter) * time_range_interval_fraction(time_range)
+            }
+        }
+    }
+}
+
+impl Forecaster<'_> {
+    // Finds the Earth time spans where a target is in effect
+    // Spans are calculated without the timing offset, which does not change the length of any wait. Intervals where
+    // the zone is not in the weather data in effect are skipped.
+    fn scan_weather_target(
+        &self,
+        zone_id: ZoneId,
+        target: &WeatherTarget,
+        start_epoch: i64,
+        interval_count: usize,
+    ) -> TargetScan {
+        let first_interval_index = weather_interval_index(self.raw_time(start_epoch));
+        let mut previous = self.forecast_interval(zone_id, first_interval_index - 1);
+        let mut scan = TargetScan {
+            spans: TimeWindowSet::new(),
+            interval_starts: Vec::with_capacity(interval_count),
+            matching_intervals: 0,
+        };
+        for interval_index in first_interval_index..first_interval_index + interval_count as i64 {
+            let Some(forecast) = self.forecast_interval(zone_id, interval_index) else {
+                previous = None;
+                continue;
+            };
+            let (start_time, end_time) = weather_interval_bounds(interval_index);
+            let interval_spans = match target {
+                WeatherTarget::Weather(filter) if filter.matches(forecast.weather) => {
+                    vec![(start_time, end_time)]
+                }
+                WeatherTarget::Transition {
+                    previous: before,
+                    current,
+                } if previous
+                    .as_ref()
+                    .is_some_and(|previous| before.matches(previous.weather))
+                    && current.matches(forecast.weather) =>
+                {
+                    vec![(start_time, end_time)]
+                }
+                WeatherTarget::TimeWindow { filter, time_range }
+                    if filter.matches(forecast.weather) =>
+                {
+                    time_range.earth_windows(start_time, end_time)
+                }
+                _ => Vec::new(),
+            };
+            scan.interval_starts.push(start_time);
+            if !interval_spans.is_empty() {
+                scan.matching_intervals += 1;
+            }
+            scan.spans.extend(interval_spans);
+            previous = Some(forecast);
+        }
+        scan
+    }
+
+    /// Scans the forecast for the waits until a weather target, e.g. to decide whether a camp is worth it
+    /// The scan starts with the interval containing `start_time`. Waits are measured from the start of each scanned
+    /// interval until the target is next in effect, so an interval containing the target has a wait of zero unless
+    /// the target only starts part way through it.
+    ///
+    /// # Arguments
+    /// - `zone` - The name or `ZoneId` of the zone
+    /// - `target` - The weather conditions
+    /// - `start_time` - The time to start scanning from
+    /// - `horizon` - How far ahead to scan
+    ///
+    /// # Returns
+    /// - The theoretical chance of the target in the weather data in effect at `start_time`, along with the observed waits
+    pub fn analyze_weather_wait<Z: ZoneKey, T: ToUnixTimestamp>(
+        &self,
+        zone: Z,
+        target: &WeatherTarget,
+        start_time: T,
+        horizon: SearchHorizon,
+    ) -> WaitStatistics {
+        let start_epoch = start_time.to_unix_timestamp();
+        let zone_id = self.expect_zone_at(&zone, self.raw_time(start_epoch)).id;
+        // The chance uses the version of the weather data in effect at the start of the scan
+        let probability =
+            self.database()
+                .weather_probability(zone_id, target, self.raw_time(start_epoch));
+        let interval_count = horizon.interval_count(start_epoch, ForecastDirection::Forward) + 1;
+        let scan = self.scan_weather_target(zone_id, target, start_epoch, interval_count);
+
+        let mut waits: Vec<i64> = scan
+            .interval_starts
+            .iter()
+            .filter_map(|interval_start| {
+                let next_span = scan.spans.next_window(*interval_start)?;
+                Some((next_span.0 - interval_start).max(0))
+            })
+            .collect();
+        waits.sort_unstable();
+        // Nearest rank percentile of the sorted waits
+        let percentile = |fraction: f64| -> Option<Duration> {
+            let rank = ((fraction * waits.len() as f64).ceil() as usize).max(1);
+            waits.get(rank - 1).map(|wait| Duration::seconds(*wait))
+        };
+        let mean_wait = if waits.is_empty() {
+            None
+        } else {
+            Some(seconds_to_duration(
+                waits.iter().sum::<i64>() as f64 / waits.len() as f64,
+            ))
+        };
+
+        let (scan_start, scan_end) =
+            match (scan.interval_starts.first(), scan.interval_starts.last()) {
+                (Some(first), Some(last)) => (*first, last + EARTH_SECONDS_PER_WEATHER_INTERVAL),
+                _ => (self.raw_time(start_epoch), self.raw_time(start_epoch)),
+            };
+        let mut longest_drought = 0;
+        let mut drought_start = scan_start;
+        for (span_start, span_end) in &scan.spans {
+            longest_drought = longest_drought.max(span_start - drought_start);
+            drought_start = *span_end;
+        }
+        longest_drought = longest_drought.max(scan_end - drought_start);
+
+        WaitStatistics {
+            probability,
+            intervals_scanned: scan.interval_starts.len(),
+            observed_probability: scan.matching_intervals as f64
+                / scan.interval_starts.len().max(1) as f64,
+            occurrences: scan.spans.len(),
+            mean_wait,
+            median_wait: percentile(0.5),
+            p95_wait: percentile(0.95),
+            longest_drought: Duration::seconds(longest_drought),
+        }
+    }
+}
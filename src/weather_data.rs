@@ -0,0 +1,578 @@
+use crate::eorzean_time::ToUnixTimestamp;
+use crate::eorzean_weather::Weather;
+
+use chrono::DateTime;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+// Weather table bundled with the crate, parsed once on first use
+static BUILTIN_WEATHER_TABLE: Lazy<WeatherTable> = Lazy::new(|| {
+    WeatherTable::from_json_str(include_str!("../data/weather_data.json"))
+        .expect("Unable to parse the weather data")
+});
+
+static BUILTIN_WEATHER_DATABASE: Lazy<WeatherDatabase> =
+    Lazy::new(|| WeatherDatabase::new(WeatherTable::builtin().clone()));
+
+/// Identifier of a zone's weather table
+///
+/// Ids are the game's TerritoryType row ids (e.g. 134 for Middle La Noscea), which is also what the
+/// datamine importer produces. Unlike zone names, which are not unique (e.g. "Mist" and "The Diadem"
+/// each have two tables), every entry in a `WeatherTable` has its own id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize)]
+pub struct ZoneId(pub u32);
+
+impl fmt::Display for ZoneId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// The weather rates of a single zone
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ZoneWeather {
+    pub id: ZoneId,
+    pub name: String,
+    /// Alternative names the zone can be looked up by
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Cumulative chances in ascending order, the weather is the first entry whose chance is above the forecast target
+    pub rates: Vec<(Weather, i32)>,
+}
+
+impl ZoneWeather {
+    /// Gets the weather of the zone for a forecast target
+    ///
+    /// # Arguments
+    /// - `forecast_target` - The target returned by `calculate_weather_forecast_target` (0-99)
+    ///
+    /// # Returns
+    /// - The matching weather, or `None` if the rates of the zone do not cover the target
+    pub fn weather_for_target(&self, forecast_target: i32) -> Option<Weather> {
+        self.rates
+            .iter()
+            .find(|(_, chance)| forecast_target < *chance)
+            .map(|(weather, _)| *weather)
+    }
+
+    /// Gets the chance of each weather of the zone
+    /// Weathers listed in several rows of the rates (e.g. Gridania's two Rain rows) are combined into one entry
+    ///
+    /// # Returns
+    /// - A Vec of weathers and their chance in percent, in the order they first appear in the rates
+    pub fn weather_chances(&self) -> Vec<(Weather, i32)> {
+        let mut chances: Vec<(Weather, i32)> = Vec::new();
+        let mut previous_threshold = 0;
+        for (weather, threshold) in &self.rates {
+            let chance = (threshold - previous_threshold).max(0);
+            previous_threshold = previous_threshold.max(*threshold);
+            match chances.iter_mut().find(|(existing, _)| existing == weather) {
+                Some((_, total)) => *total += chance,
+                None => chances.push((*weather, chance)),
+            }
+        }
+        chances
+    }
+
+    /// Gets the chance of a weather in the zone, in percent
+    pub fn weather_chance(&self, weather: Weather) -> i32 {
+        self.weather_chances()
+            .into_iter()
+            .find(|(existing, _)| *existing == weather)
+            .map_or(0, |(_, chance)| chance)
+    }
+
+    /// Checks whether the zone is called `name`, either by its name or one of its aliases
+    pub fn is_named(&self, name: &str) -> bool {
+        self.name == name || self.aliases.iter().any(|alias| alias == name)
+    }
+}
+
+// Quotes a string as a JSON string literal
+fn json_string(value: &str) -> String {
+    serde_json::Value::String(value.to_string()).to_string()
+}
+
+/// Errors that can occur while loading a weather table
+#[derive(Debug)]
+pub enum WeatherDataError {
+    /// The data could not be read
+    Io(io::Error),
+    /// The data is not valid JSON or does not follow the weather table schema
+    Json(serde_json::Error),
+    /// Two zones in the data share the same id
+    DuplicateZoneId { id: ZoneId, name: String },
+    /// A version's go-live date is neither Unix seconds nor an RFC 3339 date
+    InvalidEffectiveFrom {
+        patch: Option<String>,
+        value: String,
+    },
+    /// Two versions go live at the same time
+    ConflictingVersions { effective_from: Option<i64> },
+    /// The data does not contain any version of the weather table
+    NoVersions,
+}
+
+impl fmt::Display for WeatherDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WeatherDataError::Io(error) => write!(f, "Unable to read the weather data: {}", error),
+            WeatherDataError::Json(error) => write!(f, "Invalid weather data: {}", error),
+            WeatherDataError::DuplicateZoneId { id, name } => {
+                write!(
+                    f,
+                    "Duplicate zone id {} ('{}') in the weather data",
+                    id.0, name
+                )
+            }
+            WeatherDataError::InvalidEffectiveFrom { patch, value } => write!(
+                f,
+                "Invalid effective_from {} for patch {}",
+                value,
+                patch.as_deref().unwrap_or("(unnamed)")
+            ),
+            WeatherDataError::ConflictingVersions {
+                effective_from: Some(timestamp),
+            } => {
+                write!(f, "Several weather data versions go live at {}", timestamp)
+            }
+            WeatherDataError::ConflictingVersions {
+                effective_from: None,
+            } => {
+                write!(f, "Several weather data versions have no go-live date")
+            }
+            WeatherDataError::NoVersions => write!(f, "The weather data has no versions"),
+        }
+    }
+}
+
+impl std::error::Error for WeatherDataError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WeatherDataError::Io(error) => Some(error),
+            WeatherDataError::Json(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for WeatherDataError {
+    fn from(error: io::Error) -> Self {
+        WeatherDataError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for WeatherDataError {
+    fn from(error: serde_json::Error) -> Self {
+        WeatherDataError::Json(error)
+    }
+}
+
+/// A zone name that is shared by more than one table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateZoneName {
+    pub name: String,
+    /// Ids of every table using the name, in the order they are defined
+    pub ids: Vec<ZoneId>,
+}
+
+/// A set of zone weather tables, such as the one in `data/weather_data.json`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WeatherTable {
+    zones: Vec<ZoneWeather>,
+}
+
+impl WeatherTable {
+    /// Parses a weather table from JSON
+    ///
+    /// # Arguments
+    /// - `json` - A JSON array of zones with `id`, `name`, `aliases` and `rates` fields
+    ///
+    /// # Returns
+    /// - The parsed table, or an error if the JSON is malformed or two zones share an id
+    pub fn from_json_str(json: &str) -> Result<WeatherTable, WeatherDataError> {
+        let zones: Vec<ZoneWeather> = serde_json::from_str(json)?;
+        WeatherTable::from_zones(zones)
+    }
+
+    /// Creates a weather table from a list of zones
+    ///
+    /// # Returns
+    /// - The table, or an error if two zones share an id
+    pub fn from_zones(zones: Vec<ZoneWeather>) -> Result<WeatherTable, WeatherDataError> {
+        let mut seen_ids = HashSet::new();
+        for zone in &zones {
+            if !seen_ids.insert(zone.id) {
+                return Err(WeatherDataError::DuplicateZoneId {
+                    id: zone.id,
+                    name: zone.name.clone(),
+                });
+            }
+        }
+        Ok(WeatherTable { zones })
+    }
+
+    /// Merges another table into this one
+    /// Zones of `other` replace the zones with the same id, and zones with new ids are added after the existing ones.
+    /// Since name lookups prefer the last zone defined, added zones also take precedence over existing zones of the same name.
+    ///
+    /// # Arguments
+    /// - `other` - The table whose zones take precedence
+    pub fn merge(&mut self, other: &WeatherTable) {
+        for zone in &other.zones {
+            match self
+                .zones
+                .iter_mut()
+                .find(|existing| existing.id == zone.id)
+            {
+                Some(existing) => *existing = zone.clone(),
+                None => self.zones.push(zone.clone()),
+            }
+        }
+    }
+
+    /// Writes the table as JSON in the layout of `data/weather_data.json`
+    pub fn to_json_string(&self) -> String {
+        let zones: Vec<String> = self
+            .zones
+            .iter()
+            .map(|zone| {
+                let rates: Vec<String> = zone
+                    .rates
+                    .iter()
+                    .map(|(weather, chance)| format!("      [{}, {}]", json_string(&weather.to_string()), chance))
+                    .collect();
+                let aliases: Vec<String> = zone.aliases.iter().map(|alias| json_string(alias)).collect();
+                format!(
+                    "  {{\n    \"id\": {},\n    \"name\": {},\n    \"aliases\": [{}],\n    \"rates\": [\n{}\n    ]\n  }}",
+                    zone.id.0,
+                    json_string(&zone.name),
+                    aliases.join(", "),
+                    rates.join(",\n")
+                )
+            })
+            .collect();
+        format!("[\n{}\n]\n", zones.join(",\n"))
+    }
+
+    /// Gets the weather table bundled with the crate
+    pub fn builtin() -> &'static WeatherTable {
+        &BUILTIN_WEATHER_TABLE
+    }
+
+    /// Gets every zone in the table, in the order they are defined
+    pub fn zones(&self) -> &[ZoneWeather] {
+        &self.zones
+    }
+
+    /// Finds the zones where a weather can occur
+    ///
+    /// # Returns
+    /// - A Vec of zones and the chance of the weather in percent, the most likely zone first.
+    ///   Zones with the same chance keep their table order.
+    pub fn zones_with_weather(&self, weather: Weather) -> Vec<(&ZoneWeather, i32)> {
+        let mut zones: Vec<(&ZoneWeather, i32)> = self
+            .zones
+            .iter()
+            .map(|zone| (zone, zone.weather_chance(weather)))
+            .filter(|(_, chance)| *chance > 0)
+            .collect();
+        zones.sort_by(|(_, a), (_, b)| b.cmp(a));
+        zones
+    }
+
+    /// Finds a zone by its id
+    pub fn zone_by_id(&self, id: ZoneId) -> Option<&ZoneWeather> {
+        self.zones.iter().find(|zone| zone.id == id)
+    }
+
+    /// Finds a zone by its exact name or alias
+    ///
+    /// If several zones share the name, the last one defined is returned. Use `zones_by_name`
+    /// or `zone_by_id` to address the others.
+    pub fn zone_by_name(&self, name: &str) -> Option<&ZoneWeather> {
+        self.zones.iter().rev().find(|zone| zone.is_named(name))
+    }
+
+    /// Finds every zone with the exact name or alias, in the order they are defined
+    pub fn zones_by_name(&self, name: &str) -> Vec<&ZoneWeather> {
+        self.zones
+            .iter()
+            .filter(|zone| zone.is_named(name))
+            .collect()
+    }
+
+    /// Reports the zone names that are used by more than one table
+    ///
+    /// # Returns
+    /// - The duplicated names sorted alphabetically, along with the ids of the tables sharing them
+    pub fn duplicate_names(&self) -> Vec<DuplicateZoneName> {
+        let mut ids_by_name: BTreeMap<&str, Vec<ZoneId>> = BTreeMap::new();
+        for zone in &self.zones {
+            ids_by_name
+                .entry(zone.name.as_str())
+                .or_default()
+                .push(zone.id);
+        }
+        ids_by_name
+            .into_iter()
+            .filter(|(_, ids)| ids.len() > 1)
+            .map(|(name, ids)| DuplicateZoneName {
+                name: name.to_string(),
+                ids,
+            })
+            .collect()
+    }
+}
+
+/// A weather table together with the game patch it was live in
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherDataVersion {
+    /// The game patch that introduced the table (e.g. "7.0")
+    pub patch: Option<String>,
+    /// Unix timestamp from which the table is in effect, `None` if it is in effect since the beginning
+    pub effective_from: Option<i64>,
+    pub table: WeatherTable,
+}
+
+// A version in the JSON format of a versioned database
+#[derive(Deserialize)]
+struct VersionRecord {
+    patch: Option<String>,
+    #[serde(default)]
+    effective_from: Option<serde_json::Value>,
+    zones: Vec<ZoneWeather>,
+}
+
+// Reads a go-live date given either as Unix seconds or as an RFC 3339 date
+fn parse_effective_from(
+    patch: &Option<String>,
+    value: serde_json::Value,
+) -> Result<i64, WeatherDataError> {
+    let timestamp = match &value {
+        serde_json::Value::Number(number) => number.as_i64(),
+        serde_json::Value::String(date) => DateTime::parse_from_rfc3339(date)
+            .ok()
+            .map(|date| date.timestamp()),
+        _ => None,
+    };
+    timestamp.ok_or_else(|| WeatherDataError::InvalidEffectiveFrom {
+        patch: patch.clone(),
+        value: value.to_string(),
+    })
+}
+
+/// The weather data used to calculate forecasts
+///
+/// The free functions in `eorzean_weather` use `WeatherDatabase::builtin()`. A database loaded at runtime,
+/// for example with PTR data, offers the same forecast functions as methods.
+///
+/// A database can hold several versions of the weather table, each with the date it went live. Forecasts
+/// use the version that was in effect at the time being forecast.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherDatabase {
+    // Sorted by effective_from, with at most one version in effect since the beginning
+    versions: Vec<WeatherDataVersion>,
+}
+
+impl Default for WeatherDatabase {
+    fn default() -> Self {
+        WeatherDatabase::new(WeatherTable::default())
+    }
+}
+
+impl WeatherDatabase {
+    /// Creates a database from a single weather table, in effect at all times
+    pub fn new(table: WeatherTable) -> WeatherDatabase {
+        WeatherDatabase {
+            versions: vec![WeatherDataVersion {
+                patch: None,
+                effective_from: None,
+                table,
+            }],
+        }
+    }
+
+    /// Creates a database from several versions of the weather table
+    ///
+    /// # Arguments
+    /// - `versions` - The versions in any order. Times before the earliest go-live date use the earliest version
+    ///
+    /// # Returns
+    /// - The database, or an error if there are no versions or two versions share a go-live date
+    pub fn from_versions(
+        mut versions: Vec<WeatherDataVersion>,
+    ) -> Result<WeatherDatabase, WeatherDataError> {
+        if versions.is_empty() {
+            return Err(WeatherDataError::NoVersions);
+        }
+        versions.sort_by_key(|version| version.effective_from);
+        for pair in versions.windows(2) {
+            if pair[0].effective_from == pair[1].effective_from {
+                return Err(WeatherDataError::ConflictingVersions {
+                    effective_from: pair[1].effective_from,
+                });
+            }
+        }
+        Ok(WeatherDatabase { versions })
+    }
+
+    /// Gets the database of the weather data bundled with the crate
+    pub fn builtin() -> &'static WeatherDatabase {
+        &BUILTIN_WEATHER_DATABASE
+    }
+
+    /// Parses a database from JSON
+    /// Accepts either the format of `data/weather_data.json`, or an object with a `versions` array whose entries
+    /// have a `patch`, an `effective_from` date (Unix seconds or RFC 3339) and the `zones` of that version.
+    pub fn from_json_str(json: &str) -> Result<WeatherDatabase, WeatherDataError> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        if value.is_array() {
+            let zones: Vec<ZoneWeather> = serde_json::from_value(value)?;
+            return Ok(WeatherDatabase::new(WeatherTable::from_zones(zones)?));
+        }
+
+        #[derive(Deserialize)]
+        struct VersionedRecord {
+            versions: Vec<VersionRecord>,
+        }
+        let record: VersionedRecord = serde_json::from_value(value)?;
+        let mut versions = Vec::with_capacity(record.versions.len());
+        for version in record.versions {
+            let effective_from = match version.effective_from {
+                Some(value) => Some(parse_effective_from(&version.patch, value)?),
+                None => None,
+            };
+            versions.push(WeatherDataVersion {
+                patch: version.patch,
+                effective_from,
+                table: WeatherTable::from_zones(version.zones)?,
+            });
+        }
+        WeatherDatabase::from_versions(versions)
+    }
+
+    /// Reads a database from JSON, see `from_json_str` for the accepted formats
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<WeatherDatabase, WeatherDataError> {
+        let mut json = String::new();
+        reader.read_to_string(&mut json)?;
+        WeatherDatabase::from_json_str(&json)
+    }
+
+    /// Reads a database from a JSON file, see `from_json_str` for the accepted formats
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<WeatherDatabase, WeatherDataError> {
+        WeatherDatabase::from_json_str(&fs::read_to_string(path)?)
+    }
+
+    /// Creates a database of the bundled weather data with the zones of `table` merged over it
+    /// See `WeatherTable::merge` for how zones are replaced.
+    pub fn builtin_merged_with(table: &WeatherTable) -> WeatherDatabase {
+        let mut database = WeatherDatabase::builtin().clone();
+        database.merge(table);
+        database
+    }
+
+    /// Gets every version of the weather table, ordered by go-live date
+    pub fn versions(&self) -> &[WeatherDataVersion] {
+        &self.versions
+    }
+
+    /// Finds the version introduced by a patch
+    pub fn version(&self, patch: &str) -> Option<&WeatherDataVersion> {
+        self.versions
+            .iter()
+            .find(|version| version.patch.as_deref() == Some(patch))
+    }
+
+    /// Gets the version of the weather table in effect at a given time
+    ///
+    /// # Arguments
+    /// - `time` - A type that implements the `ToUnixTimestamp` trait (i64 or chrono::DateTime<Utc>)
+    ///
+    /// # Returns
+    /// - The latest version live at `time`, or the earliest version if `time` predates all of them
+    pub fn version_at<T: ToUnixTimestamp>(&self, time: T) -> &WeatherDataVersion {
+        let timestamp = time.to_unix_timestamp();
+        self.versions
+            .iter()
+            .rev()
+            .find(|version| version.effective_from.is_none_or(|from| from <= timestamp))
+            .unwrap_or(&self.versions[0])
+    }
+
+    /// Gets the weather table in effect at a given time
+    pub fn table_at<T: ToUnixTimestamp>(&self, time: T) -> &WeatherTable {
+        &self.version_at(time).table
+    }
+
+    /// Gets the latest version of the weather table
+    pub fn table(&self) -> &WeatherTable {
+        &self.versions[self.versions.len() - 1].table
+    }
+
+    /// Adds a version of the weather table
+    ///
+    /// # Returns
+    /// - An error if a version with the same go-live date already exists
+    pub fn add_version(&mut self, version: WeatherDataVersion) -> Result<(), WeatherDataError> {
+        if self
+            .versions
+            .iter()
+            .any(|existing| existing.effective_from == version.effective_from)
+        {
+            return Err(WeatherDataError::ConflictingVersions {
+                effective_from: version.effective_from,
+            });
+        }
+        self.versions.push(version);
+        self.versions.sort_by_key(|version| version.effective_from);
+        Ok(())
+    }
+
+    /// Merges a table into the latest version, replacing zones with the same id
+    pub fn merge(&mut self, table: &WeatherTable) {
+        let latest = self.versions.len() - 1;
+        self.versions[latest].table.merge(table);
+    }
+}
+
+/// Handles the ways a zone can be referred to (name or `ZoneId`)
+pub trait ZoneKey: fmt::Display {
+    /// Finds the zone referred to by the key in a table
+    fn find_zone<'a>(&self, table: &'a WeatherTable) -> Option<&'a ZoneWeather>;
+}
+
+impl ZoneKey for str {
+    /// Looks the zone up by name or alias, falling back to `WeatherTable::resolve_zone` for inexact names
+    fn find_zone<'a>(&self, table: &'a WeatherTable) -> Option<&'a ZoneWeather> {
+        table
+            .zone_by_name(self)
+            .or_else(|| table.resolve_zone(self).zone())
+    }
+}
+
+/// References to keys, such as `&str` and `&String`, look the zone up like the key they refer to
+impl<K: ZoneKey + ?Sized> ZoneKey for &K {
+    fn find_zone<'a>(&self, table: &'a WeatherTable) -> Option<&'a ZoneWeather> {
+        (**self).find_zone(table)
+    }
+}
+
+impl ZoneKey for String {
+    /// Looks the zone up by name or alias, falling back to `WeatherTable::resolve_zone` for inexact names
+    fn find_zone<'a>(&self, table: &'a WeatherTable) -> Option<&'a ZoneWeather> {
+        self.as_str().find_zone(table)
+    }
+}
+
+impl ZoneKey for ZoneId {
+    /// Looks the zone up by id
+    fn find_zone<'a>(&self, table: &'a WeatherTable) -> Option<&'a ZoneWeather> {
+        table.zone_by_id(*self)
+    }
+}
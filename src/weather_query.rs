@@ -0,0 +1,506 @@
+use crate::eorzean_time::{
+    moon_phase_window_set, EorzeanTimeRange, ToUnixTimestamp, EORZEA_MOON_PHASES,
+};
+use crate::eorzean_weather::{
+    weather_in_zone, weather_interval_bounds, weather_interval_index, Weather, WeatherFilter,
+};
+use crate::forecaster::Forecaster;
+use crate::time_window_set::TimeWindowSet;
+use crate::weather_data::{WeatherDatabase, ZoneId, ZoneKey};
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A condition of a weather query
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryCondition {
+    /// `zone:` - The zone the weather conditions apply to, looked up when the query is evaluated
+    Zone(String),
+    /// `weather:` - The weather of the zone matches the filter
+    Weather(WeatherFilter),
+    /// `after:` - The weather of the zone in the interval before matches the filter
+    After(WeatherFilter),
+    /// `et:` - The Eorzean time of day is within any of the ranges
+    EorzeanTime(Vec<EorzeanTimeRange>),
+    /// `moon:` - The moon is in any of the phases, given as indices in `EORZEA_MOON_PHASES`
+    MoonPhase(Vec<usize>),
+}
+
+/// A parsed weather query, which matches the times where all of its conditions hold
+/// Queries are written as space separated `key:value` terms, with alternatives separated by `|` and values
+/// containing spaces in double quotes, e.g. `zone:"Middle La Noscea" weather:Rain|Showers after:Fog et:06:00-10:00`
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherQuery {
+    pub conditions: Vec<QueryCondition>,
+}
+
+/// Errors that can occur while parsing or evaluating a weather query
+/// Columns count characters from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryError {
+    /// The query has no terms
+    EmptyQuery,
+    /// A term does not start with a key followed by a colon
+    ExpectedKey { column: usize, found: String },
+    /// The key of a term is not one of zone, weather, after, et or moon
+    UnknownKey { column: usize, key: String },
+    /// A term, or one of its alternatives, has no value
+    MissingValue { column: usize, key: String },
+    /// A quoted value has no closing quote
+    UnterminatedQuote { column: usize },
+    /// A term is followed by something other than a space
+    UnexpectedCharacter { column: usize, character: char },
+    /// A value is not valid for its key
+    InvalidValue {
+        column: usize,
+        key: String,
+        value: String,
+        expected: &'static str,
+    },
+    /// More than one zone was given
+    MultipleZones { column: usize },
+    /// A weather condition was given without a zone
+    MissingZone,
+    /// The zone is not in the weather data
+    UnknownZone {
+        zone: String,
+        suggestions: Vec<String>,
+    },
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::EmptyQuery => write!(f, "The query has no conditions"),
+            QueryError::ExpectedKey { column, found } => {
+                write!(
+                    f,
+                    "Expected a term such as weather:Rain at column {}, found '{}'",
+                    column, found
+                )
+            }
+            QueryError::UnknownKey { column, key } => write!(
+                f,
+                "Unknown key '{}' at column {}, expected one of zone, weather, after, et or moon",
+                key, column
+            ),
+            QueryError::MissingValue { column, key } => {
+                write!(f, "Missing a value for '{}' at column {}", key, column)
+            }
+            QueryError::UnterminatedQuote { column } => {
+                write!(f, "The quote at column {} is never closed", column)
+            }
+            QueryError::UnexpectedCharacter { column, character } => write!(
+                f,
+                "Unexpected '{}' at column {}, terms must be separated by spaces",
+                character, column
+            ),
+            QueryError::InvalidValue {
+                column,
+                key,
+                value,
+                expected,
+            } => write!(
+                f,
+                "Invalid value '{}' for '{}' at column {}, expected {}",
+                value, key, column, expected
+            ),
+            QueryError::MultipleZones { column } => {
+                write!(
+                    f,
+                    "Only one zone can be given, found another at column {}",
+                    column
+                )
+            }
+            QueryError::MissingZone => write!(
+                f,
+                "Weather conditions need a zone, e.g. zone:\"Middle La Noscea\""
+            ),
+            QueryError::UnknownZone { zone, suggestions } => {
+                write!(f, "Unknown zone '{}'", zone)?;
+                if !suggestions.is_empty() {
+                    write!(f, ", did you mean '{}'?", suggestions.join("', '"))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+// Lowercases a name and drops everything but letters and digits, so "clearskies" matches "Clear Skies"
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+fn parse_weather(value: &str) -> Option<Weather> {
+    value.parse().ok().or_else(|| {
+        let value = normalize_name(value);
+        Weather::ALL
+            .into_iter()
+            .find(|weather| normalize_name(&weather.to_string()) == value)
+    })
+}
+
+fn parse_moon_phase(value: &str) -> Option<usize> {
+    let value = normalize_name(value);
+    EORZEA_MOON_PHASES
+        .iter()
+        .position(|phase| normalize_name(phase) == value)
+}
+
+// Parses an Eorzean time as HH:MM or HH, 24:00 is accepted as midnight
+fn parse_eorzean_time(value: &str) -> Option<(u8, u8)> {
+    let (bells, minutes) = value.split_once(':').unwrap_or((value, "0"));
+    let bells: u8 = bells.parse().ok()?;
+    let minutes: u8 = minutes.parse().ok()?;
+    (bells < 24 && minutes < 60 || bells == 24 && minutes == 0).then_some((bells, minutes))
+}
+
+fn parse_time_range(value: &str) -> Option<EorzeanTimeRange> {
+    let (start, end) = value.split_once('-')?;
+    Some(EorzeanTimeRange::new(
+        parse_eorzean_time(start)?,
+        parse_eorzean_time(end)?,
+    ))
+}
+
+// A term of a query as written, before its values are interpreted
+struct Term {
+    column: usize,
+    key: String,
+    // Each alternative with its column
+    values: Vec<(usize, String)>,
+}
+
+// Splits a query into terms
+struct Tokenizer {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Tokenizer {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn take_while<F: Fn(char) -> bool>(&mut self, predicate: F) -> String {
+        let start = self.position;
+        while self.peek().is_some_and(&predicate) {
+            self.position += 1;
+        }
+        self.chars[start..self.position].iter().collect()
+    }
+
+    fn next_term(&mut self) -> Result<Option<Term>, QueryError> {
+        self.take_while(char::is_whitespace);
+        if self.peek().is_none() {
+            return Ok(None);
+        }
+        let column = self.position + 1;
+        let key = self.take_while(|c| c.is_alphabetic());
+        if key.is_empty() || self.peek() != Some(':') {
+            self.position = column - 1;
+            let found = self.take_while(|c| !c.is_whitespace());
+            return Err(QueryError::ExpectedKey { column, found });
+        }
+        self.position += 1;
+
+        let mut values = Vec::new();
+        loop {
+            let value_column = self.position + 1;
+            let value = if self.peek() == Some('"') {
+                self.position += 1;
+                let value = self.take_while(|c| c != '"');
+                if self.peek().is_none() {
+                    return Err(QueryError::UnterminatedQuote {
+                        column: value_column,
+                    });
+                }
+                self.position += 1;
+                value
+            } else {
+                self.take_while(|c| !c.is_whitespace() && c != '|' && c != '"')
+            };
+            if value.trim().is_empty() {
+                return Err(QueryError::MissingValue {
+                    column: value_column,
+                    key,
+                });
+            }
+            values.push((value_column, value.trim().to_string()));
+            if self.peek() != Some('|') {
+                break;
+            }
+            self.position += 1;
+        }
+        match self.peek() {
+            Some(character) if !character.is_whitespace() => Err(QueryError::UnexpectedCharacter {
+                column: self.position + 1,
+                character,
+            }),
+            _ => Ok(Some(Term {
+                column,
+                key,
+                values,
+            })),
+        }
+    }
+}
+
+impl Term {
+    // Interprets each alternative of the term, or reports the first that is invalid
+    fn parse_values<V, F: Fn(&str) -> Option<V>>(
+        &self,
+        parse: F,
+        expected: &'static str,
+    ) -> Result<Vec<V>, QueryError> {
+        self.values
+            .iter()
+            .map(|(column, value)| {
+                parse(value).ok_or_else(|| QueryError::InvalidValue {
+                    column: *column,
+                    key: self.key.clone(),
+                    value: value.clone(),
+                    expected,
+                })
+            })
+            .collect()
+    }
+
+    fn into_condition(self, has_zone: bool) -> Result<QueryCondition, QueryError> {
+        const WEATHER_EXPECTED: &str = "a weather such as Rain or \"Clear Skies\"";
+        match self.key.to_lowercase().as_str() {
+            "zone" => {
+                if has_zone {
+                    return Err(QueryError::MultipleZones {
+                        column: self.column,
+                    });
+                }
+                if let Some((column, _)) = self.values.get(1) {
+                    return Err(QueryError::MultipleZones { column: *column });
+                }
+                Ok(QueryCondition::Zone(self.values[0].1.clone()))
+            }
+            "weather" => Ok(QueryCondition::Weather(WeatherFilter::any_of(
+                self.parse_values(parse_weather, WEATHER_EXPECTED)?,
+            ))),
+            "after" => Ok(QueryCondition::After(WeatherFilter::any_of(
+                self.parse_values(parse_weather, WEATHER_EXPECTED)?,
+            ))),
+            "et" => Ok(QueryCondition::EorzeanTime(self.parse_values(
+                parse_time_range,
+                "a range of Eorzean time such as 06:00-10:00",
+            )?)),
+            "moon" => Ok(QueryCondition::MoonPhase(self.parse_values(
+                parse_moon_phase,
+                "a moon phase such as \"New Moon\"",
+            )?)),
+            _ => Err(QueryError::UnknownKey {
+                column: self.column,
+                key: self.key,
+            }),
+        }
+    }
+}
+
+impl WeatherQuery {
+    /// Parses a weather query
+    ///
+    /// # Arguments
+    /// - `query` - Space separated terms, each one of:
+    ///   - `zone:<name>` - The zone for the weather conditions, exact or inexact as with `WeatherTable::resolve_zone`
+    ///   - `weather:<weather>` - The weather of the zone
+    ///   - `after:<weather>` - The weather of the zone in the interval before
+    ///   - `et:<HH:MM-HH:MM>` - A range of Eorzean time, which can wrap past midnight
+    ///   - `moon:<phase>` - A moon phase from `EORZEA_MOON_PHASES`
+    ///
+    ///   Values can list alternatives separated by `|`, and need double quotes if they contain spaces.
+    ///   Weathers and moon phases ignore case, spaces and punctuation.
+    ///
+    /// # Returns
+    /// - The parsed query, or an error describing the first problem found
+    pub fn parse(query: &str) -> Result<WeatherQuery, QueryError> {
+        let mut tokenizer = Tokenizer {
+            chars: query.chars().collect(),
+            position: 0,
+        };
+        let mut conditions = Vec::new();
+        while let Some(term) = tokenizer.next_term()? {
+            let has_zone = conditions
+                .iter()
+                .any(|condition| matches!(condition, QueryCondition::Zone(_)));
+            conditions.push(term.into_condition(has_zone)?);
+        }
+        if conditions.is_empty() {
+            return Err(QueryError::EmptyQuery);
+        }
+        let query = WeatherQuery { conditions };
+        if query.zone().is_none()
+            && query.conditions.iter().any(|condition| {
+                matches!(
+                    condition,
+                    QueryCondition::Weather(_) | QueryCondition::After(_)
+                )
+            })
+        {
+            return Err(QueryError::MissingZone);
+        }
+        Ok(query)
+    }
+
+    /// Gets the zone of the query, if it has one
+    pub fn zone(&self) -> Option<&str> {
+        self.conditions
+            .iter()
+            .find_map(|condition| match condition {
+                QueryCondition::Zone(zone) => Some(zone.as_str()),
+                _ => None,
+            })
+    }
+}
+
+impl FromStr for WeatherQuery {
+    type Err = QueryError;
+
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        WeatherQuery::parse(query)
+    }
+}
+
+/// Finds the times matching a weather query within a span, using the bundled weather data
+/// See `WeatherQuery::parse` and `Forecaster::query_windows`
+pub fn query_weather_windows<T: ToUnixTimestamp>(
+    query: &str,
+    start_time: T,
+    end_time: T,
+) -> Result<TimeWindowSet, QueryError> {
+    WeatherDatabase::builtin().forecaster().query_windows(
+        &WeatherQuery::parse(query)?,
+        start_time,
+        end_time,
+    )
+}
+
+impl Forecaster<'_> {
+    // Finds the zone of a query in the weather data in effect at a given time
+    fn query_zone_id(&self, zone: &str, epoch: i64) -> Result<ZoneId, QueryError> {
+        let table = self.database().table_at(epoch);
+        match zone.find_zone(table) {
+            Some(zone) => Ok(zone.id),
+            None => {
+                let mut suggestions: Vec<String> = Vec::new();
+                for zone_match in table.suggest_zones(zone, 3) {
+                    if !suggestions.contains(&zone_match.zone.name) {
+                        suggestions.push(zone_match.zone.name.clone());
+                    }
+                }
+                Err(QueryError::UnknownZone {
+                    zone: zone.to_string(),
+                    suggestions,
+                })
+            }
+        }
+    }
+
+    /// Finds the times matching a weather query within a span
+    /// Weather conditions match whole weather intervals, which are then narrowed down by the Eorzean time and moon
+    /// phase conditions
+    ///
+    /// # Arguments
+    /// - `query` - The parsed query
+    /// - `start_time` - The start of the span (inclusive)
+    /// - `end_time` - The end of the span (exclusive)
+    ///
+    /// # Returns
+    /// - The matching times with the timing offset applied and clipped to the span, or an error if the zone of the
+    ///   query is not in the weather data
+    pub fn query_windows<T: ToUnixTimestamp>(
+        &self,
+        query: &WeatherQuery,
+        start_time: T,
+        end_time: T,
+    ) -> Result<TimeWindowSet, QueryError> {
+        let start_epoch = start_time.to_unix_timestamp();
+        let end_epoch = end_time.to_unix_timestamp();
+        let zone_id = query
+            .zone()
+            .map(|zone| self.query_zone_id(zone, start_epoch))
+            .transpose()?;
+        if end_epoch <= start_epoch {
+            return Ok(TimeWindowSet::new());
+        }
+        // Conditions are evaluated without the timing offset, which is applied to the result. The span is extended
+        // by an Earth minute in case the precision moves the start of a window into it.
+        let raw_start = self.raw_time(start_epoch);
+        let raw_end = self.raw_time(end_epoch) + 60;
+        let mut windows = TimeWindowSet::from_window(raw_start, raw_end);
+
+        let mut weather_filters = Vec::new();
+        let mut after_filters = Vec::new();
+        for condition in &query.conditions {
+            match condition {
+                QueryCondition::Zone(_) => {}
+                QueryCondition::Weather(filter) => weather_filters.push(filter),
+                QueryCondition::After(filter) => after_filters.push(filter),
+                QueryCondition::EorzeanTime(time_ranges) => {
+                    let matching =
+                        time_ranges
+                            .iter()
+                            .fold(TimeWindowSet::new(), |matching, time_range| {
+                                matching.union(&time_range.earth_window_set(raw_start, raw_end))
+                            });
+                    windows = windows.intersection(&matching);
+                }
+                QueryCondition::MoonPhase(phases) => {
+                    let matching = phases.iter().fold(TimeWindowSet::new(), |matching, phase| {
+                        matching.union(&moon_phase_window_set(*phase, raw_start, raw_end))
+                    });
+                    windows = windows.intersection(&matching);
+                }
+            }
+        }
+
+        if let Some(zone_id) =
+            zone_id.filter(|_| !weather_filters.is_empty() || !after_filters.is_empty())
+        {
+            let weather_at = |interval_index: i64| {
+                let (interval_start, _) = weather_interval_bounds(interval_index);
+                let zone = self
+                    .database()
+                    .table_at(interval_start)
+                    .zone_by_id(zone_id)?;
+                Some(weather_in_zone(zone, interval_start))
+            };
+            let matches_all = |filters: &[&WeatherFilter], weather: Option<Weather>| {
+                filters
+                    .iter()
+                    .all(|filter| weather.is_some_and(|weather| filter.matches(weather)))
+            };
+            let first_index = weather_interval_index(raw_start);
+            let mut previous = weather_at(first_index - 1);
+            let mut matching = TimeWindowSet::new();
+            for interval_index in first_index..=weather_interval_index(raw_end - 1) {
+                let current = weather_at(interval_index);
+                if matches_all(&weather_filters, current) && matches_all(&after_filters, previous) {
+                    let (interval_start, interval_end) = weather_interval_bounds(interval_index);
+                    matching.insert(interval_start, interval_end);
+                }
+                previous = current;
+            }
+            windows = windows.intersection(&matching);
+        }
+
+        let windows: TimeWindowSet = windows
+            .into_iter()
+            .map(|(window_start, window_end)| {
+                (self.adjust_time(window_start), self.adjust_time(window_end))
+            })
+            .collect();
+        Ok(windows.clip(start_epoch, end_epoch))
+    }
+}
@@ -0,0 +1,165 @@
+use crate::eorzean_time::{EarthTimeSpan, EorzeanTimeRange, ToUnixTimestamp};
+use crate::eorzean_weather::{
+    calculate_weather_forecast_target, weather_interval_bounds, weather_interval_index, Weather,
+};
+use crate::forecaster::Forecaster;
+use crate::time_window_set::TimeWindowSet;
+use crate::weather_data::{WeatherDatabase, ZoneId, ZoneKey};
+
+/// The weather seen in a zone, such as in a screenshot
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherObservation<Z: ZoneKey> {
+    pub zone: Z,
+    pub weather: Weather,
+    /// The Eorzean time of day the observation was made in, if known
+    pub eorzean_time: Option<EorzeanTimeRange>,
+    /// The weather of the zone in the interval before the observation, if known
+    pub previous_weather: Option<Weather>,
+}
+
+impl<Z: ZoneKey> WeatherObservation<Z> {
+    /// Creates an observation of a weather in a zone
+    pub fn new(zone: Z, weather: Weather) -> WeatherObservation<Z> {
+        WeatherObservation {
+            zone,
+            weather,
+            eorzean_time: None,
+            previous_weather: None,
+        }
+    }
+
+    /// Sets the Eorzean time of day the observation was made in
+    pub fn at_eorzean_time(mut self, eorzean_time: EorzeanTimeRange) -> WeatherObservation<Z> {
+        self.eorzean_time = Some(eorzean_time);
+        self
+    }
+
+    /// Sets the weather of the zone in the interval before the observation
+    pub fn after(mut self, previous_weather: Weather) -> WeatherObservation<Z> {
+        self.previous_weather = Some(previous_weather);
+        self
+    }
+}
+
+/// A weather interval consistent with a set of observations
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObservationMatch {
+    pub start_time: i64,
+    pub end_time: i64,
+    /// The parts of the interval that also match the Eorzean times of the observations
+    /// The whole interval if no observation has an Eorzean time
+    pub windows: Vec<(i64, i64)>,
+}
+
+impl EarthTimeSpan for ObservationMatch {
+    fn start_timestamp(&self) -> i64 {
+        self.start_time
+    }
+
+    fn end_timestamp(&self) -> i64 {
+        self.end_time
+    }
+}
+
+/// Finds the weather intervals consistent with a set of observations, using the bundled weather data
+/// See `Forecaster::solve_weather_observations`
+pub fn solve_weather_observations<Z: ZoneKey, T: ToUnixTimestamp>(
+    observations: &[WeatherObservation<Z>],
+    start_time: T,
+    end_time: T,
+) -> Vec<ObservationMatch> {
+    WeatherDatabase::builtin()
+        .forecaster()
+        .solve_weather_observations(observations, start_time, end_time)
+}
+
+impl Forecaster<'_> {
+    /// Finds the weather intervals consistent with a set of observations that were all made in the same interval,
+    /// such as screenshots of several zones taken at the same time
+    /// The forecast target of each interval is calculated once and checked against every observation.
+    ///
+    /// # Arguments
+    /// - `observations` - The weathers seen, with optional Eorzean times and previous weathers
+    /// - `start_time` - The start of the range to search (inclusive)
+    /// - `end_time` - The end of the range to search (exclusive)
+    ///
+    /// # Returns
+    /// - A Vec of every interval in the range consistent with all observations, in chronological order
+    pub fn solve_weather_observations<Z: ZoneKey, T: ToUnixTimestamp>(
+        &self,
+        observations: &[WeatherObservation<Z>],
+        start_time: T,
+        end_time: T,
+    ) -> Vec<ObservationMatch> {
+        // Intervals are found from the range without the offset, their times are reported with it
+        let start_epoch = self.raw_time(start_time.to_unix_timestamp());
+        let end_epoch = self.raw_time(end_time.to_unix_timestamp());
+        if end_epoch <= start_epoch {
+            return Vec::new();
+        }
+        let zone_ids: Vec<ZoneId> = observations
+            .iter()
+            .map(|observation| self.expect_zone_at(&observation.zone, start_epoch).id)
+            .collect();
+        let mut matches = Vec::new();
+        let mut previous_target = None;
+        for interval_index in
+            weather_interval_index(start_epoch)..=weather_interval_index(end_epoch - 1)
+        {
+            let (interval_start, interval_end) = weather_interval_bounds(interval_index);
+            let (previous_start, _) = weather_interval_bounds(interval_index - 1);
+            let forecast_target = calculate_weather_forecast_target(interval_start);
+            let previous_forecast_target = previous_target
+                .unwrap_or_else(|| calculate_weather_forecast_target(previous_start));
+            previous_target = Some(forecast_target);
+
+            let table = self.database().table_at(interval_start);
+            let previous_table = self.database().table_at(previous_start);
+            let consistent = observations
+                .iter()
+                .zip(&zone_ids)
+                .all(|(observation, zone_id)| {
+                    let weather_matches = table.zone_by_id(*zone_id).is_some_and(|zone| {
+                        zone.weather_for_target(forecast_target) == Some(observation.weather)
+                    });
+                    let previous_matches =
+                        observation.previous_weather.is_none_or(|previous_weather| {
+                            previous_table.zone_by_id(*zone_id).is_some_and(|zone| {
+                                zone.weather_for_target(previous_forecast_target)
+                                    == Some(previous_weather)
+                            })
+                        });
+                    weather_matches && previous_matches
+                });
+            if !consistent {
+                continue;
+            }
+
+            let windows = observations
+                .iter()
+                .filter_map(|observation| observation.eorzean_time.as_ref())
+                .fold(
+                    TimeWindowSet::from_window(interval_start, interval_end),
+                    |windows, eorzean_time| {
+                        windows.intersection(
+                            &eorzean_time.earth_window_set(interval_start, interval_end),
+                        )
+                    },
+                );
+            if windows.is_empty() {
+                continue;
+            }
+            matches.push(ObservationMatch {
+                start_time: self.adjust_time(interval_start),
+                end_time: self.adjust_time(interval_end),
+                windows: windows
+                    .into_iter()
+                    .map(|(window_start, window_end)| {
+                        (self.adjust_time(window_start), self.adjust_time(window_end))
+                    })
+                    .collect(),
+            });
+        }
+        matches
+    }
+}
@@ -0,0 +1,231 @@
+use crate::weather_data::{WeatherTable, ZoneWeather};
+
+use std::cmp::Ordering;
+
+/// How a query matched a zone name, from the strongest to the weakest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchKind {
+    /// The query is exactly the zone's name or one of its aliases
+    Exact,
+    /// The query matches once case, punctuation and diacritics are ignored
+    Normalized,
+    /// The words of the query and the zone name overlap (e.g. "Rak'tika" for "The Rak'tika Greatwood")
+    Words,
+    /// The query is a close misspelling of the zone name
+    Fuzzy,
+}
+
+/// A zone matching a lookup query
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoneMatch<'a> {
+    pub zone: &'a ZoneWeather,
+    /// The name or alias of the zone that matched the query
+    pub matched_name: &'a str,
+    pub kind: MatchKind,
+    /// How closely the query matched within its kind, from 0.0 to 1.0
+    pub score: f64,
+}
+
+/// The outcome of resolving a zone query
+#[derive(Debug, Clone, PartialEq)]
+pub enum ZoneResolution<'a> {
+    /// A single zone matched the query better than any other
+    Found(&'a ZoneWeather),
+    /// Several zones matched the query equally well, ranked best first
+    Ambiguous(Vec<ZoneMatch<'a>>),
+    NotFound,
+}
+
+impl<'a> ZoneResolution<'a> {
+    /// Gets the zone if the query resolved to a single one
+    pub fn zone(&self) -> Option<&'a ZoneWeather> {
+        match self {
+            ZoneResolution::Found(zone) => Some(zone),
+            _ => None,
+        }
+    }
+}
+
+/// Normalizes a zone name for comparison
+/// Letters are lowercased and stripped of diacritics, apostrophes are removed and any other punctuation separates words
+///
+/// # Arguments
+/// - `name` - The zone name or query to normalize
+///
+/// # Returns
+/// - The normalized words joined by single spaces (e.g. "Ul'dah - Steps of Thal" becomes "uldah steps of thal")
+pub fn normalize_zone_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for c in name.chars() {
+        if matches!(c, '\'' | '’' | '`' | '´') {
+            continue;
+        }
+        for c in c.to_lowercase() {
+            let c = fold_diacritic(c);
+            if c.is_alphanumeric() {
+                normalized.push(c);
+            } else if !normalized.ends_with(' ') {
+                normalized.push(' ');
+            }
+        }
+    }
+    normalized.trim().to_string()
+}
+
+// Maps accented latin letters to their base letter
+fn fold_diacritic(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' => 'a',
+        'ç' => 'c',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' => 'e',
+        'ì' | 'í' | 'î' | 'ï' | 'ī' => 'i',
+        'ñ' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' => 'o',
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' => 'u',
+        'ý' | 'ÿ' => 'y',
+        _ => c,
+    }
+}
+
+// Strips a leading article so "The Fringes" and "Fringes" compare equal
+fn strip_article(normalized: &str) -> &str {
+    normalized.strip_prefix("the ").unwrap_or(normalized)
+}
+
+// Scores the overlap between the words of a query and a name, 0.0 if neither contains the other
+fn word_overlap_score(query: &str, name: &str) -> f64 {
+    let query_words: Vec<&str> = query.split(' ').filter(|word| *word != "the").collect();
+    let name_words: Vec<&str> = name.split(' ').filter(|word| *word != "the").collect();
+    if query_words.is_empty() || name_words.is_empty() {
+        return 0.0;
+    }
+    let word_matches = |word: &str, candidates: &[&str]| {
+        candidates
+            .iter()
+            .any(|candidate| *candidate == word || (word.len() >= 3 && candidate.starts_with(word)))
+    };
+    if query_words
+        .iter()
+        .all(|word| word_matches(word, &name_words))
+    {
+        query_words.len() as f64 / name_words.len() as f64
+    } else if name_words.iter().all(|word| query_words.contains(word)) {
+        name_words.len() as f64 / query_words.len() as f64
+    } else {
+        0.0
+    }
+}
+
+// Edit distance between two strings, counted in characters
+fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous_row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current_row = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous_row[j] + usize::from(a_char != *b_char);
+            current_row.push(
+                substitution
+                    .min(previous_row[j + 1] + 1)
+                    .min(current_row[j] + 1),
+            );
+        }
+        previous_row = current_row;
+    }
+    previous_row[b.len()]
+}
+
+// Matches a query against a single name or alias of a zone
+fn match_name(query: &str, normalized_query: &str, name: &str) -> Option<(MatchKind, f64)> {
+    if query == name {
+        return Some((MatchKind::Exact, 1.0));
+    }
+    let normalized_name = normalize_zone_name(name);
+    let query_core = strip_article(normalized_query);
+    let name_core = strip_article(&normalized_name);
+    if query_core.is_empty() {
+        return None;
+    }
+    if query_core == name_core {
+        return Some((MatchKind::Normalized, 1.0));
+    }
+    let overlap = word_overlap_score(query_core, name_core);
+    if overlap > 0.0 {
+        return Some((MatchKind::Words, overlap));
+    }
+    let distance = levenshtein_distance(query_core, name_core);
+    let length = query_core.chars().count().max(name_core.chars().count());
+    if distance <= (length / 4).max(1) {
+        return Some((MatchKind::Fuzzy, 1.0 - distance as f64 / length as f64));
+    }
+    None
+}
+
+// Orders matches best first: by kind, then score, then shortest name
+fn compare_matches(a: &ZoneMatch, b: &ZoneMatch) -> Ordering {
+    a.kind
+        .cmp(&b.kind)
+        .then(b.score.total_cmp(&a.score))
+        .then(a.matched_name.len().cmp(&b.matched_name.len()))
+        .then(a.zone.name.cmp(&b.zone.name))
+}
+
+impl WeatherTable {
+    /// Lists the zones matching a query, ranked best first
+    /// Matching ignores case, punctuation and diacritics, and considers each zone's aliases
+    ///
+    /// # Arguments
+    /// - `query` - The zone name as typed by a user (e.g. "mor dhona", "MLN")
+    /// - `limit` - The maximum number of suggestions to return
+    ///
+    /// # Returns
+    /// - A Vec of matches, one per zone name. Zones sharing a name are represented by the last one defined
+    pub fn suggest_zones(&self, query: &str, limit: usize) -> Vec<ZoneMatch<'_>> {
+        let normalized_query = normalize_zone_name(query);
+        let mut matches: Vec<ZoneMatch> = Vec::new();
+        for zone in self.zones().iter().rev() {
+            if matches.iter().any(|m| m.zone.name == zone.name) {
+                continue;
+            }
+            let best = std::iter::once(&zone.name)
+                .chain(zone.aliases.iter())
+                .filter_map(|name| {
+                    match_name(query, &normalized_query, name).map(|(kind, score)| ZoneMatch {
+                        zone,
+                        matched_name: name.as_str(),
+                        kind,
+                        score,
+                    })
+                })
+                .min_by(compare_matches);
+            if let Some(best) = best {
+                matches.push(best);
+            }
+        }
+        matches.sort_by(compare_matches);
+        matches.truncate(limit);
+        matches
+    }
+
+    /// Resolves a query to a zone
+    ///
+    /// # Arguments
+    /// - `query` - The zone name as typed by a user (e.g. "uldah", "Old Sharlayan city")
+    ///
+    /// # Returns
+    /// - `Found` if one zone matches better than the others, otherwise the tied matches or `NotFound`
+    pub fn resolve_zone(&self, query: &str) -> ZoneResolution<'_> {
+        let mut matches = self.suggest_zones(query, usize::MAX);
+        if matches.is_empty() {
+            return ZoneResolution::NotFound;
+        }
+        let best_kind = matches[0].kind;
+        let best_score = matches[0].score;
+        matches.retain(|m| m.kind == best_kind && m.score == best_score);
+        if matches.len() == 1 {
+            ZoneResolution::Found(matches[0].zone)
+        } else {
+            ZoneResolution::Ambiguous(matches)
+        }
+    }
+}
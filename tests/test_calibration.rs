@@ -0,0 +1,124 @@
+extern crate ffxiv_chronowatcher;
+
+use ffxiv_chronowatcher::calibration::{
+    calibrate_timing_offset, CalibrationError, CalibrationSample,
+};
+use ffxiv_chronowatcher::clock::{Clock, FixedClock};
+use ffxiv_chronowatcher::eorzean_weather::{SearchHorizon, Weather};
+use ffxiv_chronowatcher::forecaster::Forecaster;
+use ffxiv_chronowatcher::weather_data::WeatherDatabase;
+
+mod calibration_tests {
+    use super::*;
+
+    // 1724738400 is midnight ET and the start of a weather interval
+    const MIDNIGHT: i64 = 1724738400;
+
+    #[test]
+    fn test_calibrate_weather_changes() {
+        let samples = [
+            CalibrationSample::weather_change(MIDNIGHT + 3),
+            CalibrationSample::weather_change(MIDNIGHT + 1400 + 5),
+            CalibrationSample::weather_change(MIDNIGHT + 2800 + 4),
+        ];
+        let calibration = calibrate_timing_offset(&samples).unwrap();
+        assert_eq!(calibration.offset, 4);
+        assert!((calibration.exact_offset - 4.0).abs() < 1e-9);
+        assert!((calibration.max_residual - 1.0).abs() < 1e-9);
+        assert!((calibration.standard_error.unwrap() - (1.0f64 / 3.0).sqrt()).abs() < 1e-9);
+        assert_eq!(calibration.sample_count, 3);
+    }
+
+    #[test]
+    fn test_calibrate_eorzean_minutes() {
+        // ET 08:00 starts 1400 seconds after midnight, ET 00:12 starts 35 seconds after it
+        let samples = [
+            CalibrationSample::eorzean_minute(MIDNIGHT + 1400 - 2, (8, 0)),
+            CalibrationSample::eorzean_minute(MIDNIGHT + 35 - 2, (0, 12)),
+            CalibrationSample::eorzean_minute(MIDNIGHT + 4200 - 2, (0, 0)),
+        ];
+        let calibration = calibrate_timing_offset(&samples).unwrap();
+        assert_eq!(calibration.offset, -2);
+        assert_eq!(calibration.max_residual, 0.0);
+        assert_eq!(calibration.standard_error, Some(0.0));
+    }
+
+    #[test]
+    fn test_calibrate_mixed_samples_across_wrap() {
+        // An offset near half an interval must not split into offsets of opposite signs
+        let samples = [
+            CalibrationSample::weather_change(MIDNIGHT + 699),
+            CalibrationSample::weather_change(MIDNIGHT + 1400 + 701),
+            CalibrationSample::eorzean_minute(MIDNIGHT + 1400 + 700, (8, 0)),
+        ];
+        let calibration = calibrate_timing_offset(&samples).unwrap();
+        assert_eq!(calibration.offset, 700);
+    }
+
+    #[test]
+    fn test_calibration_errors() {
+        assert_eq!(
+            calibrate_timing_offset(&[]),
+            Err(CalibrationError::NoSamples)
+        );
+        let invalid = [CalibrationSample::eorzean_minute(MIDNIGHT, (24, 0))];
+        let error = calibrate_timing_offset(&invalid).unwrap_err();
+        assert_eq!(
+            error,
+            CalibrationError::InvalidEorzeanTime {
+                bells: 24,
+                minutes: 0
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "Invalid Eorzean time 24:00 in a calibration sample"
+        );
+        let single =
+            calibrate_timing_offset(&[CalibrationSample::weather_change(MIDNIGHT - 7)]).unwrap();
+        assert_eq!(single.offset, -7);
+        assert_eq!(single.standard_error, None);
+    }
+
+    #[test]
+    fn test_forecaster_with_calibration() {
+        let calibration =
+            calibrate_timing_offset(&[CalibrationSample::weather_change(MIDNIGHT + 12)]).unwrap();
+        let forecaster = Forecaster::new(WeatherDatabase::builtin()).with_calibration(&calibration);
+        assert_eq!(forecaster.timing_offset(), 12);
+        assert_eq!(
+            forecaster
+                .calculate_forecast("Middle La Noscea", MIDNIGHT + 100, 0)
+                .start_time,
+            MIDNIGHT + 12
+        );
+    }
+
+    #[test]
+    fn test_calibrated_forecaster_near_boundary() {
+        let samples = [
+            CalibrationSample::weather_change(MIDNIGHT + 40),
+            CalibrationSample::weather_change(MIDNIGHT + 1400 + 40),
+        ];
+        let calibration = calibrate_timing_offset(&samples).unwrap();
+        let forecaster = Forecaster::new(WeatherDatabase::builtin()).with_calibration(&calibration);
+        // Middle La Noscea changes from Rain to Wind at midnight, which the calibrated clock sees at midnight + 40
+        for seconds_after_midnight in [0, 10, 39, 40, 41] {
+            let clock = FixedClock::new(MIDNIGHT + seconds_after_midnight);
+            let current = forecaster.current_weather("Middle La Noscea", &clock);
+            assert!(current.start_time <= clock.now() && clock.now() < current.end_time);
+            let change = forecaster
+                .next_weather_change("Middle La Noscea", &clock, SearchHorizon::Intervals(10))
+                .unwrap();
+            assert_eq!(change.previous, current.weather);
+            assert_eq!(change.time, current.end_time);
+        }
+        let clock = FixedClock::new(MIDNIGHT + 10);
+        assert_eq!(
+            forecaster
+                .current_weather("Middle La Noscea", &clock)
+                .weather,
+            Weather::Rain
+        );
+    }
+}
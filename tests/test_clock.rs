@@ -0,0 +1,194 @@
+extern crate ffxiv_chronowatcher;
+
+use chrono::{Duration, TimeZone, Utc};
+use ffxiv_chronowatcher::clock::{
+    current_eorzean_date, current_eorzean_time, current_weather, next_weather_change, Clock,
+    FixedClock, ManualClock, OffsetClock, SystemClock,
+};
+use ffxiv_chronowatcher::eorzean_time::{convert_to_eorzean_time, EorzeanTime};
+use ffxiv_chronowatcher::eorzean_weather::{
+    calculate_forecast, find_next_weather_change, SearchHorizon, Weather,
+};
+use ffxiv_chronowatcher::forecaster::Forecaster;
+use ffxiv_chronowatcher::weather_data::WeatherDatabase;
+
+mod clock_tests {
+    use super::*;
+
+    #[test]
+    fn test_system_clock() {
+        let before = Utc::now().timestamp();
+        let now = SystemClock.now();
+        assert!(now >= before && now <= Utc::now().timestamp());
+    }
+
+    #[test]
+    fn test_fixed_and_offset_clocks() {
+        let fixed = FixedClock::new(Utc.timestamp_opt(1724738458, 0).unwrap());
+        assert_eq!(fixed.now(), 1724738458);
+        assert_eq!(OffsetClock::new(fixed, -58).now(), 1724738400);
+        let manual = ManualClock::new(1724738458);
+        let ahead = OffsetClock::new(&manual, 10);
+        manual.advance(Duration::seconds(2));
+        assert_eq!(ahead.now(), 1724738470);
+    }
+
+    #[test]
+    fn test_manual_clock_simulates_eorzean_time() {
+        // 1724738400 is midnight ET
+        let clock = ManualClock::new(1724738400);
+        assert_eq!(current_eorzean_time(&clock), (0, 0));
+        clock.advance_eorzean(EorzeanTime {
+            years: 0,
+            moons: 0,
+            weeks: 0,
+            suns: 0,
+            bells: 8,
+            minutes: 24,
+            seconds: 0,
+        });
+        assert_eq!(current_eorzean_time(&clock), (8, 24));
+        clock.set(1724738400 + 4200);
+        assert_eq!(current_eorzean_time(&clock), (0, 0));
+        assert_eq!(current_eorzean_date(&clock).bells, 0);
+    }
+
+    #[test]
+    fn test_manual_clock_keeps_eorzean_fractions() {
+        let clock = ManualClock::new(1724738400);
+        let one_minute = || EorzeanTime {
+            years: 0,
+            moons: 0,
+            weeks: 0,
+            suns: 0,
+            bells: 0,
+            minutes: 1,
+            seconds: 0,
+        };
+        // An Eorzean minute is 35/12 Earth seconds, so single steps are not whole seconds
+        for _ in 0..60 {
+            clock.advance_eorzean(one_minute());
+        }
+        assert_eq!(current_eorzean_time(&clock), (1, 0));
+        assert_eq!(clock.now(), 1724738400 + 175);
+        clock.advance(Duration::seconds(-175));
+        assert_eq!(clock.now(), 1724738400);
+    }
+
+    #[test]
+    fn test_current_weather() {
+        let clock = ManualClock::new(1724738458);
+        assert_eq!(
+            current_weather("Middle La Noscea", &clock).weather,
+            Weather::Wind
+        );
+        clock.advance(Duration::seconds(1400));
+        assert_eq!(
+            current_weather("Middle La Noscea", &clock),
+            calculate_forecast("Middle La Noscea", 1724738458, 1)
+        );
+    }
+
+    #[test]
+    fn test_next_weather_change() {
+        let clock = ManualClock::new(1724738458);
+        let horizon = SearchHorizon::Intervals(100);
+        // Step through several hours of Eorzean time, one weather change at a time
+        for _ in 0..10 {
+            let current = current_weather("Middle La Noscea", &clock);
+            let change = next_weather_change("Middle La Noscea", &clock, horizon).unwrap();
+            assert!(change.time > clock.now());
+            assert_eq!(change.previous, current.weather);
+            assert_ne!(change.current, change.previous);
+            assert_eq!(convert_to_eorzean_time(change.time).1, 0);
+            clock.set(change.time);
+            assert_eq!(
+                current_weather("Middle La Noscea", &clock).weather,
+                change.current
+            );
+        }
+    }
+
+    #[test]
+    fn test_forecaster_reads_clock() {
+        let midnight = 1724738400;
+        let forecaster = Forecaster::new(WeatherDatabase::builtin()).with_timing_offset(40);
+        let clock = FixedClock::new(midnight + 100);
+        let current = forecaster.current_weather("Middle La Noscea", &clock);
+        assert_eq!(current.weather, Weather::Wind);
+        assert_eq!(current.start_time, midnight + 40);
+        let change = forecaster
+            .next_weather_change("Middle La Noscea", &clock, SearchHorizon::Intervals(100))
+            .unwrap();
+        assert_eq!(change.time, current.end_time);
+        assert_eq!(change.previous, Weather::Wind);
+    }
+
+    #[test]
+    fn test_forecaster_reads_clock_after_boundary() {
+        // With the offset, the change from Rain to Wind at midnight is reported at midnight + 40
+        let midnight = 1724738400;
+        let forecaster = Forecaster::new(WeatherDatabase::builtin()).with_timing_offset(40);
+        let clock = FixedClock::new(midnight + 10);
+        let current = forecaster.current_weather("Middle La Noscea", &clock);
+        assert!(current.start_time <= clock.now() && clock.now() < current.end_time);
+        assert_eq!(current.weather, Weather::Rain);
+        let change = forecaster
+            .next_weather_change("Middle La Noscea", &clock, SearchHorizon::Intervals(100))
+            .unwrap();
+        assert_eq!(change.previous, current.weather);
+        assert_eq!(change.time, current.end_time);
+
+        // Clocks behind a trait object work too
+        let clock: &dyn Clock = &clock;
+        assert_eq!(
+            forecaster.current_weather("Middle La Noscea", clock),
+            current
+        );
+    }
+
+    #[test]
+    fn test_next_weather_change_at_boundary() {
+        // Middle La Noscea changes from Rain to Wind at 1724738400, which is not after the current time
+        let change = find_next_weather_change(
+            "Middle La Noscea",
+            1724738400,
+            SearchHorizon::Intervals(100),
+        )
+        .unwrap();
+        assert!(change.time > 1724738400);
+        assert_eq!(change.previous, Weather::Wind);
+        assert_eq!(
+            find_next_weather_change("Middle La Noscea", 1724738400, SearchHorizon::Intervals(0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_next_weather_change_with_offset() {
+        let midnight = 1724738400;
+        let forecaster = Forecaster::new(WeatherDatabase::builtin()).with_timing_offset(40);
+        // With the offset, the change from Rain to Wind is reported at midnight + 40
+        let change = forecaster
+            .find_next_weather_change(
+                "Middle La Noscea",
+                midnight + 10,
+                SearchHorizon::Intervals(100),
+            )
+            .unwrap();
+        assert_eq!(change.time, midnight + 40);
+        assert_eq!(
+            (change.previous, change.current),
+            (Weather::Rain, Weather::Wind)
+        );
+        // The interval containing midnight + 10 ends at the change, so no interval after it is searched
+        assert_eq!(
+            forecaster.find_next_weather_change(
+                "Middle La Noscea",
+                midnight + 10,
+                SearchHorizon::Intervals(0)
+            ),
+            None
+        );
+    }
+}
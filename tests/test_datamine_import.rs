@@ -0,0 +1,128 @@
+extern crate ffxiv_chronowatcher;
+
+use ffxiv_chronowatcher::datamine_import::{
+    diff_weather_tables, import_datamined_sheets, ImportError, ZoneRename,
+};
+use ffxiv_chronowatcher::eorzean_weather::Weather;
+use ffxiv_chronowatcher::weather_data::{WeatherTable, ZoneId};
+
+mod datamine_import_tests {
+    use super::*;
+
+    const SHEET_DIRECTORY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/datamine");
+
+    #[test]
+    fn test_import_datamined_sheets() {
+        let import = import_datamined_sheets(SHEET_DIRECTORY, WeatherTable::builtin()).unwrap();
+        let names: Vec<&str> = import
+            .table
+            .zones()
+            .iter()
+            .map(|zone| zone.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "Limsa Lominsa",
+                "Limsa Lominsa Lower Decks",
+                "Middle La Noscea"
+            ]
+        );
+
+        let middle_la_noscea = import.table.zone_by_id(ZoneId(134)).unwrap();
+        assert_eq!(
+            middle_la_noscea.rates,
+            vec![
+                (Weather::Clouds, 20),
+                (Weather::ClearSkies, 50),
+                (Weather::FairSkies, 70),
+                (Weather::Wind, 80),
+                (Weather::Fog, 90),
+                (Weather::Rain, 100),
+            ]
+        );
+        assert_eq!(middle_la_noscea.aliases, vec!["MLN".to_string()]);
+    }
+
+    #[test]
+    fn test_import_reports_unknown_weather() {
+        let import = import_datamined_sheets(SHEET_DIRECTORY, WeatherTable::builtin()).unwrap();
+        assert_eq!(import.skipped.len(), 1);
+        assert_eq!(import.skipped[0].territory_id, 400);
+        assert_eq!(import.skipped[0].name, "The Ivory Palace, Upper");
+        assert_eq!(import.skipped[0].reason, "unknown weather 'Royal Levin'");
+    }
+
+    #[test]
+    fn test_import_missing_directory() {
+        let result = import_datamined_sheets("does/not/exist", WeatherTable::builtin());
+        assert!(matches!(result, Err(ImportError::Io { .. })));
+    }
+
+    #[test]
+    fn test_diff_weather_tables() {
+        let old = WeatherTable::from_json_str(
+            r#"[
+                {"id": 128, "name": "Limsa Lominsa Upper Decks", "rates": [["Clouds", 20], ["Clear Skies", 50], ["Fair Skies", 80], ["Fog", 90], ["Rain", 100]]},
+                {"id": 134, "name": "Middle La Noscea", "aliases": ["MLN"], "rates": [["Clouds", 100]]},
+                {"id": 156, "name": "Mor Dhona", "rates": [["Gloom", 100]]}
+            ]"#,
+        )
+        .unwrap();
+        let import = import_datamined_sheets(SHEET_DIRECTORY, &old).unwrap();
+        let diff = diff_weather_tables(&old, &import.table);
+        assert_eq!(
+            diff.added,
+            vec![(ZoneId(129), "Limsa Lominsa Lower Decks".to_string())]
+        );
+        assert_eq!(diff.removed, vec![(ZoneId(156), "Mor Dhona".to_string())]);
+        assert_eq!(
+            diff.renamed,
+            vec![ZoneRename {
+                id: ZoneId(128),
+                old_name: "Limsa Lominsa Upper Decks".to_string(),
+                new_name: "Limsa Lominsa".to_string(),
+            }]
+        );
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].id, ZoneId(134));
+        assert_eq!(diff.changed[0].old_rates, vec![(Weather::Clouds, 100)]);
+        assert_eq!(
+            diff.to_string().lines().take(3).collect::<Vec<_>>(),
+            vec![
+                "+ #129 Limsa Lominsa Lower Decks",
+                "- #156 Mor Dhona",
+                "> #128 Limsa Lominsa Upper Decks -> Limsa Lominsa",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_matches_zones_by_id() {
+        let old = WeatherTable::from_json_str(
+            r#"[{"id": 1, "name": "Middle La Noscea", "rates": [["Clouds", 100]]}]"#,
+        )
+        .unwrap();
+        let new = WeatherTable::from_json_str(
+            r#"[{"id": 134, "name": "Middle La Noscea", "rates": [["Clouds", 100]]}]"#,
+        )
+        .unwrap();
+        let diff = diff_weather_tables(&old, &new);
+        assert_eq!(
+            diff.added,
+            vec![(ZoneId(134), "Middle La Noscea".to_string())]
+        );
+        assert_eq!(
+            diff.removed,
+            vec![(ZoneId(1), "Middle La Noscea".to_string())]
+        );
+        assert!(diff.renamed.is_empty() && diff.changed.is_empty());
+    }
+
+    #[test]
+    fn test_diff_identical_tables() {
+        let diff = diff_weather_tables(WeatherTable::builtin(), WeatherTable::builtin());
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "No changes\n");
+    }
+}
@@ -1,7 +1,10 @@
 extern crate ffxiv_chronowatcher;
 
-use ffxiv_chronowatcher::eorzean_time::{convert_to_eorzean_date, convert_to_eorzean_time, earth_sec_to_eorzea_duration, eorzea_duration_to_earth_sec, EorzeanTime, EorzeanDate};
 use chrono::DateTime;
+use ffxiv_chronowatcher::eorzean_time::{
+    convert_to_eorzean_date, convert_to_eorzean_time, earth_sec_to_eorzea_duration,
+    eorzea_duration_to_earth_sec, moon_phase_window_set, EorzeanTime, EorzeanTimeRange,
+};
 
 mod time_tests {
     use super::*;
@@ -19,9 +22,10 @@ mod time_tests {
     }
 
     #[test]
-    fn test_convert_to_ez_chrono_datetime(){
+    fn test_convert_to_ez_chrono_datetime() {
         let timestamp: i64 = 141414141414;
-        let chrono_date = DateTime::from_timestamp(timestamp, 0).expect("Failed to create DateTime");
+        let chrono_date =
+            DateTime::from_timestamp(timestamp, 0).expect("Failed to create DateTime");
         let eorzean_date = convert_to_eorzean_date(chrono_date);
         assert_eq!(eorzean_date.years, 87682);
         assert_eq!(eorzean_date.suns, 18);
@@ -54,8 +58,8 @@ mod time_tests {
     }
 
     #[test]
-    fn test_earth_sec_to_eorzea_sec(){
-        let test_seconds = 70*60; // 70 minutes in seconds, should eq 1 sun = 86400 EZ sec
+    fn test_earth_sec_to_eorzea_sec() {
+        let test_seconds = 70 * 60; // 70 minutes in seconds, should eq 1 sun = 86400 EZ sec
         let eorzean_duration = earth_sec_to_eorzea_duration(test_seconds);
         assert_eq!(eorzean_duration.suns, 1);
         assert_eq!(eorzean_duration.years, 0);
@@ -67,18 +71,108 @@ mod time_tests {
 
     #[test]
     fn test_eorzea_dur_to_earth_sec() {
-        let test_ez_time = EorzeanTime{
+        let test_ez_time = EorzeanTime {
             years: 0,
             moons: 0,
             weeks: 1,
             suns: 1,
             bells: 0,
             minutes: 0,
-            seconds: 0
+            seconds: 0,
         };
         // 37800 seconds in Earth time
         let seconds = eorzea_duration_to_earth_sec(test_ez_time);
         assert_eq!(seconds, 37800);
     }
-}
 
+    #[test]
+    fn test_eorzean_time_range_contains() {
+        let range = EorzeanTimeRange::new((8, 0), (12, 0));
+        assert!(range.contains((8, 0)));
+        assert!(range.contains((11, 59)));
+        assert!(!range.contains((12, 0)));
+        let overnight = EorzeanTimeRange::new((22, 0), (2, 0));
+        assert!(overnight.contains((23, 30)));
+        assert!(overnight.contains((1, 0)));
+        assert!(!overnight.contains((2, 0)));
+        assert!(!overnight.contains((12, 0)));
+        assert!(EorzeanTimeRange::new((5, 0), (5, 0)).contains((17, 0)));
+    }
+
+    #[test]
+    fn test_eorzean_time_range_earth_windows() {
+        // 1724738400 is midnight ET
+        let day_start = 1724738400;
+        let range = EorzeanTimeRange::new((8, 0), (12, 0));
+        let windows = range.earth_windows(day_start, day_start + 4200);
+        assert_eq!(windows, vec![(day_start + 1400, day_start + 2100)]);
+        let (start, end) = windows[0];
+        assert_eq!(convert_to_eorzean_time(start), (8, 0));
+        assert_eq!(convert_to_eorzean_time(end - 1), (11, 59));
+        assert_eq!(convert_to_eorzean_time(end), (12, 0));
+    }
+
+    #[test]
+    fn test_eorzean_time_range_earth_windows_exact_seconds() {
+        let day_start = 1724738400;
+        let range = EorzeanTimeRange::new((6, 10), (6, 11));
+        let windows = range.earth_windows(day_start, day_start + 4200);
+        assert_eq!(windows.len(), 1);
+        let (start, end) = windows[0];
+        assert_eq!(convert_to_eorzean_time(start), (6, 10));
+        assert_eq!(convert_to_eorzean_time(start - 1), (6, 9));
+        assert_eq!(convert_to_eorzean_time(end - 1), (6, 10));
+        assert_eq!(convert_to_eorzean_time(end), (6, 11));
+    }
+
+    #[test]
+    fn test_eorzean_time_range_wraps_midnight() {
+        let day_start = 1724738400;
+        let range = EorzeanTimeRange::new((20, 0), (4, 0));
+        let windows = range.earth_windows(day_start, day_start + 2 * 4200);
+        // The window across midnight is joined, and the spans at either end are clipped
+        assert_eq!(
+            windows,
+            vec![
+                (day_start, day_start + 700),
+                (day_start + 3500, day_start + 4200 + 700),
+                (day_start + 4200 + 3500, day_start + 2 * 4200),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_moon_phase_boundaries() {
+        // Each phase lasts four suns starting from the 1st, so the 32nd sun is still a Waning Crescent
+        let phase_on_sun = |sun: i64| convert_to_eorzean_date((sun - 1) * 4200 + 100).phase;
+        assert_eq!(convert_to_eorzean_date(31 * 4200 + 100).suns, 32);
+        for (sun, phase) in [
+            (1, "New Moon"),
+            (4, "New Moon"),
+            (5, "Waxing Crescent"),
+            (8, "Waxing Crescent"),
+            (9, "Waxing Half Moon"),
+            (28, "Waning Half Moon"),
+            (29, "Waning Crescent"),
+            (32, "Waning Crescent"),
+            (33, "New Moon"),
+        ] {
+            assert_eq!(phase_on_sun(sun), phase, "sun {}", sun);
+        }
+        // The phase matches the windows of moon_phase_window_set
+        assert_eq!(
+            convert_to_eorzean_date(1724738399).phase,
+            "Waning Half Moon"
+        );
+        assert_eq!(convert_to_eorzean_date(1724738400).phase, "Waning Crescent");
+    }
+
+    #[test]
+    fn test_moon_phase_window_set() {
+        let windows = moon_phase_window_set(7, 1724730000, 1724760000);
+        assert_eq!(windows.windows(), &[(1724738400, 1724755200)]);
+        let windows = moon_phase_window_set(0, 0, 32 * 4200 * 2);
+        assert_eq!(windows.windows(), &[(0, 16800), (134400, 151200)]);
+        assert!(moon_phase_window_set(8, 0, 134400).is_empty());
+    }
+}
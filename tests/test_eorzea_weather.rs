@@ -1,10 +1,21 @@
 extern crate ffxiv_chronowatcher;
 
+use chrono::{Duration, FixedOffset, TimeZone, Timelike, Utc};
+use ffxiv_chronowatcher::eorzean_time::{convert_to_eorzean_time, EarthTimeSpan, EorzeanTimeRange};
+#[allow(deprecated)]
+use ffxiv_chronowatcher::eorzean_weather::find_next_weather_occurance;
 use ffxiv_chronowatcher::eorzean_weather::{
-    calculate_current_weather_interval, calculate_forecast, find_next_weather_occurance,
-    get_global_weather_timing_offset, get_weather_by_time, set_global_weather_timing_offset,
-    Weather,
+    calculate_current_weather_interval, calculate_forecast, calculate_forecast_with_neighbours,
+    calculate_weather_forecast_target, calculate_weather_interval, find_forecast_target_intervals,
+    find_longest_weather_run, find_next_matching_weather, find_next_weather_occurrence,
+    find_next_weather_run, find_previous_matching_weather, find_previous_weather_occurrence,
+    find_weather_time_windows, find_weather_transitions, forecast_iter, forecast_targets,
+    get_weather_by_time, weather_changes, weather_interval_bounds, weather_interval_index,
+    weather_snapshot, weather_snapshot_for, ForecastDirection, SearchHorizon, Weather,
+    WeatherFilter,
 };
+use ffxiv_chronowatcher::forecaster::Forecaster;
+use ffxiv_chronowatcher::weather_data::{WeatherDatabase, WeatherTable, ZoneId};
 
 mod weather_tests {
     use super::*;
@@ -12,8 +23,8 @@ mod weather_tests {
     fn test_calculate_current_weather_interval() {
         let timestamp = 1724738458;
         let (start, end) = calculate_current_weather_interval(timestamp);
-        assert_eq!(start, 1724738403);
-        assert_eq!(end, 1724739802);
+        assert_eq!(start, 1724738400);
+        assert_eq!(end, 1724739800);
     }
 
     #[test]
@@ -27,8 +38,8 @@ mod weather_tests {
         let timestamp = 1724738458;
         let weather = calculate_forecast("Middle La Noscea", timestamp, 8);
         assert_eq!(weather.weather, Weather::ClearSkies);
-        assert_eq!(weather.start_time, 1724749443);
-        assert_eq!(weather.end_time, 1724750823);
+        assert_eq!(weather.start_time, 1724749600);
+        assert_eq!(weather.end_time, 1724751000);
         assert_eq!(weather.zone_name, "Middle La Noscea");
     }
 
@@ -36,9 +47,9 @@ mod weather_tests {
     fn test_calculate_forecast_neg_offset() {
         let timestamp = 1724738458;
         let weather = calculate_forecast("Middle La Noscea", timestamp, -1);
-        assert_eq!(weather.weather, Weather::Wind);
-        assert_eq!(weather.start_time, 1724737023);
-        assert_eq!(weather.end_time, 1724738403);
+        assert_eq!(weather.weather, Weather::Rain);
+        assert_eq!(weather.start_time, 1724737000);
+        assert_eq!(weather.end_time, 1724738400);
         assert_eq!(weather.zone_name, "Middle La Noscea");
     }
 
@@ -47,18 +58,19 @@ mod weather_tests {
         let timestamp = 1724738458;
         let weather = calculate_forecast("Middle La Noscea", timestamp, 0);
         assert_eq!(weather.weather, Weather::Wind);
-        assert_eq!(weather.start_time, 1724738403);
-        assert_eq!(weather.end_time, 1724739802);
+        assert_eq!(weather.start_time, 1724738400);
+        assert_eq!(weather.end_time, 1724739800);
         assert_eq!(weather.zone_name, "Middle La Noscea");
     }
 
     #[test]
+    #[allow(deprecated)]
     fn test_find_next_weather_occurance() {
         let timestamp = 1724738458;
         let weather = find_next_weather_occurance("Eureka Pagos", timestamp, Weather::Blizzards);
         assert_eq!(weather.weather, Weather::Blizzards);
-        assert_eq!(weather.start_time, 1724742543);
-        assert_eq!(weather.end_time, 1724743923);
+        assert_eq!(weather.start_time, 1724742600);
+        assert_eq!(weather.end_time, 1724744000);
         assert_eq!(weather.zone_name, "Eureka Pagos");
     }
 
@@ -93,8 +105,68 @@ mod weather_tests {
 
     #[test]
     fn test_calculate_current_weather_interval_day_overflow() {
-        let (_, __) = calculate_current_weather_interval(1672531199);
-        assert_eq!(1, 1);
+        let (start, end) = calculate_current_weather_interval(1672531199);
+        assert_eq!(start, 1672531000);
+        assert_eq!(end, 1672532400);
+        assert_eq!(convert_to_eorzean_time(end), (0, 0));
+    }
+
+    #[test]
+    fn test_weather_interval_boundaries() {
+        assert_eq!(calculate_current_weather_interval(0), (0, 1400));
+        assert_eq!(calculate_current_weather_interval(1399), (0, 1400));
+        assert_eq!(calculate_current_weather_interval(1400), (1400, 2800));
+        assert_eq!(calculate_current_weather_interval(-1), (-1400, 0));
+        // Boundaries long before and after the present, ARR launch and the year 2100
+        assert_eq!(
+            calculate_current_weather_interval(1377993600),
+            (1377993400, 1377994800)
+        );
+        assert_eq!(
+            calculate_current_weather_interval(4102444800),
+            (4102443800, 4102445200)
+        );
+    }
+
+    #[test]
+    fn test_weather_interval_starts_on_eorzean_bells() {
+        for timestamp in [1377993600, 1724738458, 4102444800, 32503680000] {
+            let (start, end) = calculate_current_weather_interval(timestamp);
+            let (bells, minutes) = convert_to_eorzean_time(start);
+            assert_eq!(bells % 8, 0);
+            assert_eq!(minutes, 0);
+            assert_eq!(convert_to_eorzean_time(end - 1).1, 59);
+        }
+    }
+
+    #[test]
+    fn test_calculate_weather_interval_offsets_do_not_drift() {
+        let timestamp = 1724738458;
+        let (start, _) = calculate_current_weather_interval(timestamp);
+        for offset in [-100_000, -1, 1, 500, 100_000] {
+            let (offset_start, offset_end) = calculate_weather_interval(timestamp, offset);
+            assert_eq!(offset_start, start + offset * 1400);
+            assert_eq!(offset_end - offset_start, 1400);
+        }
+        let index = weather_interval_index(timestamp);
+        assert_eq!(
+            weather_interval_bounds(index + 3),
+            calculate_weather_interval(timestamp, 3)
+        );
+    }
+
+    #[test]
+    fn test_calculate_forecast_far_future() {
+        let timestamp = 4102444800;
+        let weather = calculate_forecast("Middle La Noscea", 1724738458, 1_698_361);
+        assert_eq!(
+            weather.start_time,
+            calculate_current_weather_interval(timestamp).0
+        );
+        assert_eq!(
+            weather.weather,
+            get_weather_by_time("Middle La Noscea", timestamp)
+        );
     }
 
     #[test]
@@ -105,18 +177,701 @@ mod weather_tests {
     }
 
     #[test]
-    fn test_set_global_offset() {
-        set_global_weather_timing_offset(1724738458);
-        assert_eq!(get_global_weather_timing_offset(), 1724738458);
-        set_global_weather_timing_offset(0);
+    fn test_forecast_iter_forward_matches_calculate_forecast() {
+        let timestamp = 1724738458;
+        let forecasts: Vec<_> =
+            forecast_iter("Middle La Noscea", timestamp, ForecastDirection::Forward)
+                .take(10)
+                .collect();
+        for (offset, forecast) in forecasts.iter().enumerate() {
+            assert_eq!(
+                *forecast,
+                calculate_forecast("Middle La Noscea", timestamp, offset as i32)
+            );
+        }
+        for pair in forecasts.windows(2) {
+            assert_eq!(pair[0].end_time, pair[1].start_time);
+        }
+    }
+
+    #[test]
+    fn test_forecast_iter_backward() {
+        let timestamp = 1724738458;
+        let forecasts: Vec<_> =
+            forecast_iter("Eureka Pagos", timestamp, ForecastDirection::Backward)
+                .take(5)
+                .collect();
+        assert_eq!(forecasts[0].start_time, 1724738400);
+        for (offset, forecast) in forecasts.iter().enumerate() {
+            assert_eq!(
+                *forecast,
+                calculate_forecast("Eureka Pagos", timestamp, -(offset as i32))
+            );
+        }
+    }
+
+    #[test]
+    fn test_forecast_iter_with_adapters() {
+        let timestamp = 1724738458;
+        let day_end = timestamp + 24 * 60 * 60;
+        let rainy_windows: Vec<_> =
+            forecast_iter("Middle La Noscea", timestamp, ForecastDirection::Forward)
+                .take_while(|forecast| forecast.start_time < day_end)
+                .filter(|forecast| forecast.weather == Weather::Rain)
+                .collect();
+        assert!(rainy_windows
+            .iter()
+            .all(|forecast| forecast.start_time < day_end));
+        assert_eq!(rainy_windows.len(), 8);
     }
 
     #[test]
-    fn test_find_weather_by_time_with_offset() {
-        let timestamp = 1732396696;
-        set_global_weather_timing_offset(40);
-        let weather = find_next_weather_occurance("Eureka Pagos", timestamp, Weather::Fog);
-        assert_eq!(weather.start_time, 1732401962);
-        set_global_weather_timing_offset(0);
+    fn test_find_next_weather_occurrence() {
+        let timestamp = 1724738458;
+        let weather = find_next_weather_occurrence(
+            "Eureka Pagos",
+            timestamp,
+            Weather::Blizzards,
+            SearchHorizon::Intervals(10),
+        )
+        .unwrap();
+        assert_eq!(weather.weather, Weather::Blizzards);
+        assert_eq!(weather.start_time, 1724742600);
+        assert_eq!(weather.end_time, 1724744000);
+    }
+
+    #[test]
+    fn test_find_next_weather_occurrence_outside_horizon() {
+        let timestamp = 1724738458;
+        // The next Blizzards start 3 intervals after the current one
+        assert!(find_next_weather_occurrence(
+            "Eureka Pagos",
+            timestamp,
+            Weather::Blizzards,
+            SearchHorizon::Intervals(2)
+        )
+        .is_none());
+        assert!(find_next_weather_occurrence(
+            "Eureka Pagos",
+            timestamp,
+            Weather::Blizzards,
+            SearchHorizon::Intervals(3)
+        )
+        .is_some());
+        let within_duration = SearchHorizon::Duration(Duration::seconds(1724742600 - timestamp));
+        assert!(find_next_weather_occurrence(
+            "Eureka Pagos",
+            timestamp,
+            Weather::Blizzards,
+            within_duration
+        )
+        .is_some());
+        let short_duration = SearchHorizon::Duration(Duration::seconds(1724742599 - timestamp));
+        assert!(find_next_weather_occurrence(
+            "Eureka Pagos",
+            timestamp,
+            Weather::Blizzards,
+            short_duration
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_find_next_weather_occurrence_impossible_weather() {
+        let timestamp = 1724738458;
+        let horizon = SearchHorizon::Intervals(u32::MAX);
+        assert!(
+            find_next_weather_occurrence("Limsa Lominsa", timestamp, Weather::Snow, horizon)
+                .is_none()
+        );
+        assert!(find_previous_weather_occurrence(
+            "Limsa Lominsa",
+            timestamp,
+            Weather::Snow,
+            horizon
+        )
+        .is_none());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    #[should_panic]
+    fn test_find_next_weather_occurance_impossible_weather_panics() {
+        find_next_weather_occurance("Limsa Lominsa", 1724738458, Weather::Snow);
+    }
+
+    #[test]
+    fn test_find_previous_weather_occurrence() {
+        let timestamp = 1724738458;
+        let weather = find_previous_weather_occurrence(
+            "Middle La Noscea",
+            timestamp,
+            Weather::Rain,
+            SearchHorizon::Intervals(10),
+        )
+        .unwrap();
+        assert_eq!(weather.weather, Weather::Rain);
+        assert_eq!(weather.start_time, 1724737000);
+        assert_eq!(weather.end_time, 1724738400);
+        let current = calculate_forecast("Middle La Noscea", timestamp, 0);
+        let earlier = find_previous_weather_occurrence(
+            "Middle La Noscea",
+            timestamp,
+            current.weather,
+            SearchHorizon::Intervals(1000),
+        )
+        .unwrap();
+        assert!(earlier.end_time <= current.start_time);
+    }
+
+    #[test]
+    fn test_weather_filter_matches() {
+        let filter = WeatherFilter::any_of([Weather::ClearSkies, Weather::FairSkies]);
+        assert!(filter.matches(Weather::ClearSkies));
+        assert!(filter.matches(Weather::FairSkies));
+        assert!(!filter.matches(Weather::Rain));
+        let filter = WeatherFilter::none_of([Weather::Fog]);
+        assert!(filter.matches(Weather::Rain));
+        assert!(!filter.matches(Weather::Fog));
+        let filter =
+            WeatherFilter::any_of([Weather::Fog, Weather::Rain]).excluding([Weather::Rain]);
+        assert!(!filter.matches(Weather::Rain));
+    }
+
+    #[test]
+    fn test_find_next_matching_weather() {
+        let timestamp = 1724738458;
+        let horizon = SearchHorizon::Intervals(100);
+        let filter = WeatherFilter::any_of([Weather::Rain, Weather::Fog]);
+        let weather =
+            find_next_matching_weather("Middle La Noscea", timestamp, &filter, horizon).unwrap();
+        let rain =
+            find_next_weather_occurrence("Middle La Noscea", timestamp, Weather::Rain, horizon)
+                .unwrap();
+        let fog =
+            find_next_weather_occurrence("Middle La Noscea", timestamp, Weather::Fog, horizon)
+                .unwrap();
+        assert_eq!(weather.start_time, rain.start_time.min(fog.start_time));
+        assert!(filter.matches(weather.weather));
+    }
+
+    #[test]
+    fn test_find_matching_weather_excluding() {
+        let timestamp = 1724738458;
+        let horizon = SearchHorizon::Intervals(100);
+        let current = calculate_forecast("Middle La Noscea", timestamp, 0);
+        let filter = WeatherFilter::none_of([current.weather]);
+        let next_change =
+            find_next_matching_weather("Middle La Noscea", timestamp, &filter, horizon).unwrap();
+        assert_ne!(next_change.weather, current.weather);
+        let mut forecasts =
+            forecast_iter("Middle La Noscea", timestamp, ForecastDirection::Forward).skip(1);
+        assert_eq!(
+            forecasts.find(|forecast| forecast.weather != current.weather),
+            Some(next_change)
+        );
+        let previous_change =
+            find_previous_matching_weather("Middle La Noscea", timestamp, &filter, horizon)
+                .unwrap();
+        assert!(previous_change.end_time <= current.start_time);
+    }
+
+    #[test]
+    fn test_find_matching_weather_impossible_set() {
+        let filter = WeatherFilter::any_of([Weather::Snow, Weather::Blizzards]);
+        let horizon = SearchHorizon::Intervals(u32::MAX);
+        assert!(
+            find_next_matching_weather("Limsa Lominsa", 1724738458, &filter, horizon).is_none()
+        );
+    }
+
+    #[test]
+    fn test_find_weather_transitions() {
+        let timestamp = 1724738458;
+        let fog = WeatherFilter::from(Weather::Fog);
+        let clear = WeatherFilter::any_of([Weather::ClearSkies, Weather::FairSkies]);
+        let transitions = find_weather_transitions(
+            "Middle La Noscea",
+            timestamp,
+            &fog,
+            &clear,
+            3,
+            SearchHorizon::Intervals(1000),
+        );
+        assert_eq!(transitions.len(), 3);
+        for transition in &transitions {
+            assert_eq!(transition.previous.weather, Weather::Fog);
+            assert!(clear.matches(transition.current.weather));
+            assert_eq!(transition.previous.end_time, transition.current.start_time);
+            assert!(transition.previous.start_time >= 1724738400);
+        }
+        assert!(transitions
+            .windows(2)
+            .all(|pair| pair[0].current.start_time < pair[1].current.start_time));
+    }
+
+    #[test]
+    fn test_find_weather_transitions_matches_forecast() {
+        let timestamp = 1724738458;
+        let any = WeatherFilter::default();
+        let transitions = find_weather_transitions(
+            "Eureka Pagos",
+            timestamp,
+            &any,
+            &any,
+            5,
+            SearchHorizon::Intervals(100),
+        );
+        for (offset, transition) in transitions.iter().enumerate() {
+            assert_eq!(
+                transition.previous,
+                calculate_forecast("Eureka Pagos", timestamp, offset as i32)
+            );
+            assert_eq!(
+                transition.current,
+                calculate_forecast("Eureka Pagos", timestamp, offset as i32 + 1)
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_weather_transitions_bounded() {
+        let timestamp = 1724738458;
+        let snow = WeatherFilter::from(Weather::Snow);
+        let rain = WeatherFilter::from(Weather::Rain);
+        assert!(find_weather_transitions(
+            "Limsa Lominsa",
+            timestamp,
+            &snow,
+            &rain,
+            1,
+            SearchHorizon::Intervals(u32::MAX)
+        )
+        .is_empty());
+        let any = WeatherFilter::default();
+        assert_eq!(
+            find_weather_transitions(
+                "Limsa Lominsa",
+                timestamp,
+                &any,
+                &any,
+                10,
+                SearchHorizon::Intervals(4)
+            )
+            .len(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_find_weather_time_windows() {
+        let timestamp = 1724738458;
+        let rain = WeatherFilter::from(Weather::Rain);
+        let range = EorzeanTimeRange::new((6, 0), (10, 0));
+        let windows = find_weather_time_windows(
+            "Middle La Noscea",
+            timestamp,
+            &rain,
+            &range,
+            5,
+            SearchHorizon::Intervals(1000),
+        );
+        assert_eq!(windows.len(), 5);
+        for window in &windows {
+            assert_eq!(window.forecast.weather, Weather::Rain);
+            assert!(
+                window.forecast.start_time <= window.start_time
+                    && window.end_time <= window.forecast.end_time
+            );
+            assert!(range.contains(convert_to_eorzean_time(window.start_time)));
+            assert!(range.contains(convert_to_eorzean_time(window.end_time - 1)));
+            assert!(
+                !range.contains(convert_to_eorzean_time(window.end_time))
+                    || window.end_time == window.forecast.end_time
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_weather_time_windows_includes_current_interval() {
+        // The current interval (00:00 to 08:00 ET) has Wind
+        let timestamp = 1724738458;
+        let wind = WeatherFilter::from(Weather::Wind);
+        let range = EorzeanTimeRange::new((22, 0), (2, 0));
+        let windows = find_weather_time_windows(
+            "Middle La Noscea",
+            timestamp,
+            &wind,
+            &range,
+            1,
+            SearchHorizon::Intervals(0),
+        );
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].start_time, 1724738400);
+        assert_eq!(windows[0].end_time, 1724738750);
+    }
+
+    #[test]
+    fn test_find_weather_time_windows_impossible() {
+        let snow = WeatherFilter::from(Weather::Snow);
+        let range = EorzeanTimeRange::new((0, 0), (0, 0));
+        assert!(find_weather_time_windows(
+            "Limsa Lominsa",
+            1724738458,
+            &snow,
+            &range,
+            1,
+            SearchHorizon::Intervals(u32::MAX)
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_find_next_weather_run() {
+        let timestamp = 1724738458;
+        let wet = WeatherFilter::any_of([Weather::Rain, Weather::Fog]);
+        let run = find_next_weather_run(
+            "Middle La Noscea",
+            timestamp,
+            &wet,
+            3,
+            SearchHorizon::Intervals(10_000),
+        )
+        .unwrap();
+        assert!(run.length() >= 3);
+        assert!(run.start_time > timestamp);
+        assert_eq!(run.start_time, run.forecasts[0].start_time);
+        assert_eq!(run.end_time, run.start_time + 1400 * run.length() as i64);
+        assert!(run
+            .forecasts
+            .iter()
+            .all(|forecast| wet.matches(forecast.weather)));
+        // The run is complete on both ends
+        let before = calculate_forecast("Middle La Noscea", run.start_time, -1);
+        let after = calculate_forecast("Middle La Noscea", run.end_time, 0);
+        assert!(!wet.matches(after.weather));
+        assert!(!wet.matches(before.weather) || before.start_time <= timestamp);
+        // No earlier run of three intervals exists
+        let earlier = find_next_weather_run(
+            "Middle La Noscea",
+            timestamp,
+            &wet,
+            3,
+            SearchHorizon::Duration(Duration::seconds(run.start_time - timestamp)),
+        );
+        assert_eq!(earlier, None);
+    }
+
+    #[test]
+    fn test_find_longest_weather_run() {
+        let timestamp = 1724738458;
+        let rain = WeatherFilter::from(Weather::Rain);
+        let horizon = SearchHorizon::Intervals(500);
+        let longest =
+            find_longest_weather_run("Middle La Noscea", timestamp, &rain, horizon).unwrap();
+        let mut expected = 0;
+        let mut current = 0;
+        for forecast in forecast_iter("Middle La Noscea", timestamp, ForecastDirection::Forward)
+            .skip(1)
+            .take(500)
+        {
+            current = if forecast.weather == Weather::Rain {
+                current + 1
+            } else {
+                0
+            };
+            expected = expected.max(current);
+        }
+        assert_eq!(longest.length(), expected);
+        assert_eq!(
+            find_next_weather_run("Middle La Noscea", timestamp, &rain, expected, horizon),
+            Some(longest)
+        );
+        assert_eq!(
+            find_next_weather_run("Middle La Noscea", timestamp, &rain, expected + 1, horizon),
+            None
+        );
+    }
+
+    #[test]
+    fn test_weather_run_is_cut_off_at_horizon() {
+        let any_weather = WeatherFilter::default();
+        let run = find_longest_weather_run(
+            "Middle La Noscea",
+            1724738458,
+            &any_weather,
+            SearchHorizon::Intervals(12),
+        )
+        .unwrap();
+        assert_eq!(run.length(), 12);
+        assert_eq!(run.start_time, 1724739800);
+        assert_eq!(run.end_time, 1724739800 + 12 * 1400);
+    }
+
+    #[test]
+    fn test_find_weather_run_impossible() {
+        let snow = WeatherFilter::from(Weather::Snow);
+        assert_eq!(
+            find_next_weather_run(
+                "Limsa Lominsa",
+                1724738458,
+                &snow,
+                1,
+                SearchHorizon::Intervals(u32::MAX)
+            ),
+            None
+        );
+        assert_eq!(
+            find_longest_weather_run(
+                "Limsa Lominsa",
+                1724738458,
+                &snow,
+                SearchHorizon::Intervals(u32::MAX)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_weather_snapshot_all_zones() {
+        let timestamp = 1724738458;
+        let snapshot = weather_snapshot(timestamp, 3);
+        assert_eq!(snapshot.time, timestamp);
+        assert_eq!(snapshot.zones.len(), WeatherTable::builtin().zones().len());
+        for zone in &snapshot.zones {
+            assert_eq!(
+                zone.previous,
+                calculate_forecast(zone.zone_id, timestamp, -1)
+            );
+            assert_eq!(zone.current, calculate_forecast(zone.zone_id, timestamp, 0));
+            assert_eq!(zone.upcoming.len(), 3);
+            for (offset, forecast) in (1..).zip(&zone.upcoming) {
+                assert_eq!(
+                    forecast,
+                    &calculate_forecast(zone.zone_id, timestamp, offset)
+                );
+            }
+        }
+        let middle_la_noscea = snapshot.zone_by_name("Middle La Noscea").unwrap();
+        assert_eq!(middle_la_noscea.previous.weather, Weather::Rain);
+        assert_eq!(middle_la_noscea.current.weather, Weather::Wind);
+        assert_eq!(snapshot.zone_by_id(ZoneId(339)).unwrap().zone_name, "Mist");
+    }
+
+    #[test]
+    fn test_weather_snapshot_for_zones() {
+        let timestamp = 1724738458;
+        let snapshot = weather_snapshot_for(&["Eureka Pagos", "MLN"], timestamp, 0);
+        let names: Vec<&str> = snapshot
+            .zones
+            .iter()
+            .map(|zone| zone.zone_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Eureka Pagos", "Middle La Noscea"]);
+        assert!(snapshot.zones.iter().all(|zone| zone.upcoming.is_empty()));
+        assert_eq!(snapshot.zones[1].current.start_time, 1724738400);
+        assert_eq!(
+            weather_snapshot_for(&[ZoneId(134)], timestamp, 2).zones,
+            weather_snapshot_for(&["MLN"], timestamp, 2).zones
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Unable to find the zone")]
+    fn test_weather_snapshot_for_unknown_zone() {
+        weather_snapshot_for(&["Somewhere Not Here"], 1724738458, 1);
+    }
+
+    #[test]
+    fn test_weather_changes_match_forecasts() {
+        let zones = ["Middle La Noscea", "Eureka Pagos", "Mor Dhona"];
+        let start_time = 1724738400;
+        let end_time = start_time + 200 * 1400;
+        let changes: Vec<_> = weather_changes(&zones, start_time, end_time).collect();
+        assert!(changes.windows(2).all(|pair| pair[0].time <= pair[1].time));
+        assert!(changes
+            .iter()
+            .all(|change| change.time >= start_time && change.time < end_time));
+        assert!(changes
+            .iter()
+            .all(|change| change.previous != change.current));
+        for zone in zones {
+            let mut expected = Vec::new();
+            let forecasts: Vec<_> = forecast_iter(zone, start_time - 1, ForecastDirection::Forward)
+                .take(201)
+                .collect();
+            for pair in forecasts.windows(2) {
+                if pair[0].weather != pair[1].weather {
+                    expected.push((pair[0].weather, pair[1].weather, pair[1].start_time));
+                }
+            }
+            let actual: Vec<_> = changes
+                .iter()
+                .filter(|change| change.zone_name == zone)
+                .map(|change| (change.previous, change.current, change.time))
+                .collect();
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_weather_changes_range_bounds() {
+        // Middle La Noscea changes from Rain to Wind at 1724738400
+        let first = weather_changes(&["Middle La Noscea"], 1724738400, 1724738401)
+            .next()
+            .unwrap();
+        assert_eq!(
+            (first.previous, first.current, first.time),
+            (Weather::Rain, Weather::Wind, 1724738400)
+        );
+        assert_eq!(
+            weather_changes(&["Middle La Noscea"], 1724738401, 1724739800).next(),
+            None
+        );
+        assert_eq!(
+            weather_changes::<&str, i64>(&[], 1724738400, 1824738400).next(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_weather_changes_with_offset() {
+        let midnight = 1724738400;
+        let forecaster = Forecaster::new(WeatherDatabase::builtin()).with_timing_offset(40);
+        let changes: Vec<_> = forecaster
+            .weather_changes(&["Middle La Noscea"], midnight + 10, midnight + 100)
+            .collect();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(
+            (changes[0].previous, changes[0].current, changes[0].time),
+            (Weather::Rain, Weather::Wind, midnight + 40)
+        );
+        assert_eq!(
+            forecaster
+                .weather_changes(&["Middle La Noscea"], midnight + 41, midnight + 1440)
+                .next(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_forecast_targets() {
+        let targets: Vec<(i64, i32)> =
+            forecast_targets(1724738458, 1724738458 + 3 * 1400).collect();
+        assert_eq!(targets.len(), 4);
+        assert_eq!(targets[0].0, 1724738400);
+        for (offset, (start_time, forecast_target)) in (0..).zip(&targets) {
+            assert_eq!(*start_time, 1724738400 + offset * 1400);
+            assert_eq!(
+                *forecast_target,
+                calculate_weather_forecast_target(*start_time)
+            );
+            assert!((0..100).contains(forecast_target));
+        }
+        assert_eq!(forecast_targets(1724738400, 1724739800).len(), 1);
+        assert_eq!(forecast_targets(1724738400, 1724738400).next(), None);
+        let reversed: Vec<(i64, i32)> = forecast_targets(1724738458, 1724738458 + 3 * 1400)
+            .rev()
+            .collect();
+        assert_eq!(reversed, targets.into_iter().rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_find_forecast_target_intervals() {
+        let start_time = 1724738400;
+        let end_time = start_time + 1000 * 1400;
+        let low = find_forecast_target_intervals(start_time, end_time, 0..5);
+        assert!(!low.is_empty());
+        assert!(low.iter().all(|(_, forecast_target)| *forecast_target < 5));
+        let all = find_forecast_target_intervals(start_time, end_time, ..);
+        assert_eq!(all.len(), 1000);
+        let high = find_forecast_target_intervals(start_time, end_time, 5..=99);
+        assert_eq!(low.len() + high.len(), 1000);
+        // Gridania has Rain for targets below 20
+        for (interval_start, _) in find_forecast_target_intervals(start_time, end_time, ..20) {
+            assert_eq!(
+                get_weather_by_time("Gridania", interval_start),
+                Weather::Rain
+            );
+        }
+    }
+
+    #[test]
+    fn test_forecast_typed_accessors() {
+        let weather = calculate_forecast("Middle La Noscea", 1724738458, 0);
+        assert_eq!(
+            weather.start_datetime(),
+            Utc.timestamp_opt(1724738400, 0).unwrap()
+        );
+        assert_eq!(
+            weather.end_datetime(),
+            Utc.timestamp_opt(1724739800, 0).unwrap()
+        );
+        let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+        let start_in_tokyo = weather.start_datetime_in(&tokyo);
+        assert_eq!(start_in_tokyo, weather.start_datetime());
+        assert_eq!(
+            start_in_tokyo.hour(),
+            (weather.start_datetime().hour() + 9) % 24
+        );
+        assert_eq!(weather.duration(), Duration::seconds(1400));
+        assert_eq!(weather.remaining_from(1724738458), Duration::seconds(1342));
+        assert_eq!(
+            weather.remaining_from(Utc.timestamp_opt(1724739805, 0).unwrap()),
+            Duration::zero()
+        );
+        assert!(weather.contains_time(1724738458));
+        assert!(!weather.contains_time(1724739800));
+        let start_date = weather.start_eorzean_date();
+        assert_eq!((start_date.bells, start_date.minutes), (0, 0));
+        let end_date = weather.end_eorzean_date();
+        assert_eq!((end_date.bells, end_date.minutes), (8, 0));
+    }
+
+    #[test]
+    fn test_other_forecast_structs_typed_accessors() {
+        let any_weather = WeatherFilter::default();
+        let run =
+            find_longest_weather_run("MLN", 1724738458, &any_weather, SearchHorizon::Intervals(3))
+                .unwrap();
+        assert_eq!(run.duration(), Duration::seconds(3 * 1400));
+        assert_eq!(
+            run.start_datetime(),
+            Utc.timestamp_opt(1724739800, 0).unwrap()
+        );
+        let wind = WeatherFilter::from(Weather::Wind);
+        let range = EorzeanTimeRange::new((22, 0), (2, 0));
+        let window = &find_weather_time_windows(
+            "MLN",
+            1724738458,
+            &wind,
+            &range,
+            1,
+            SearchHorizon::Intervals(0),
+        )[0];
+        assert_eq!(window.duration(), Duration::seconds(350));
+        assert_eq!(window.end_eorzean_date().bells, 2);
+        let change = weather_changes(&["MLN"], 1724738400, 1724738401)
+            .next()
+            .unwrap();
+        assert_eq!(change.datetime(), Utc.timestamp_opt(1724738400, 0).unwrap());
+        assert_eq!(change.eorzean_date().bells, 0);
+        assert_eq!((1724738400, 1724738750).duration(), Duration::seconds(350));
+    }
+
+    #[test]
+    fn test_calculate_forecast_with_neighbours() {
+        let weather = calculate_forecast_with_neighbours("MLN", 1724738458, 0);
+        assert_eq!(weather.previous_weather, Some(Weather::Rain));
+        assert_eq!(weather.weather, Weather::Wind);
+        assert_eq!(weather.next_weather, Some(Weather::FairSkies));
+        assert_eq!(
+            weather.previous_weather,
+            Some(calculate_forecast("MLN", 1724738458, -1).weather)
+        );
+        assert_eq!(
+            calculate_forecast("MLN", 1724738458, 0).previous_weather,
+            None
+        );
     }
 }
@@ -0,0 +1,208 @@
+extern crate ffxiv_chronowatcher;
+
+use ffxiv_chronowatcher::eorzean_time::EorzeanTimeRange;
+use ffxiv_chronowatcher::eorzean_weather::{
+    ForecastDirection, SearchHorizon, Weather, WeatherFilter,
+};
+use ffxiv_chronowatcher::forecaster::{Forecaster, TimePrecision};
+use ffxiv_chronowatcher::weather_data::WeatherDatabase;
+
+mod forecaster_tests {
+    use super::*;
+
+    #[test]
+    fn test_forecaster_defaults() {
+        let forecaster = Forecaster::new(WeatherDatabase::builtin());
+        assert_eq!(forecaster.timing_offset(), 0);
+        assert_eq!(forecaster.precision(), TimePrecision::Second);
+        assert!(!forecaster.neighbouring_weather());
+        let weather = forecaster.calculate_forecast("Middle La Noscea", 1724738458, 0);
+        assert_eq!(weather.weather, Weather::Wind);
+        assert_eq!(
+            (weather.previous_weather, weather.next_weather),
+            (None, None)
+        );
+        assert_eq!(weather.start_time, 1724738400);
+        assert_eq!(weather.end_time, 1724739800);
+    }
+
+    #[test]
+    fn test_forecaster_timing_offset() {
+        let forecaster = Forecaster::new(WeatherDatabase::builtin()).with_timing_offset(40);
+        let weather = forecaster.calculate_forecast("Middle La Noscea", 1724738458, 0);
+        assert_eq!(weather.weather, Weather::Wind);
+        assert_eq!(
+            (weather.start_time, weather.end_time),
+            (1724738440, 1724739840)
+        );
+        let fog = forecaster
+            .find_next_weather_occurrence(
+                "Eureka Pagos",
+                1732396696,
+                Weather::Fog,
+                SearchHorizon::Intervals(100),
+            )
+            .unwrap();
+        assert_eq!(fog.start_time, 1732402040);
+        let next = forecaster
+            .forecast_iter("Middle La Noscea", 1724738458, ForecastDirection::Forward)
+            .nth(1)
+            .unwrap();
+        assert_eq!(next.start_time, 1724739840);
+        let snapshot = forecaster.weather_snapshot_for(&["MLN"], 1724738458, 1);
+        assert_eq!(snapshot.zones[0].current, weather);
+        assert_eq!(snapshot.zones[0].upcoming[0], next);
+    }
+
+    #[test]
+    fn test_forecaster_offset_window_after_boundary() {
+        // Middle La Noscea changes from Rain to Wind at midnight, which the offset reports at midnight + 40
+        let midnight = 1724738400;
+        let now = midnight + 10;
+        let forecaster = Forecaster::new(WeatherDatabase::builtin()).with_timing_offset(40);
+        let current = forecaster.calculate_forecast("Middle La Noscea", now, 0);
+        assert_eq!(current.weather, Weather::Rain);
+        assert!(current.start_time <= now && now < current.end_time);
+        assert_eq!(current.end_time, midnight + 40);
+
+        let first = forecaster
+            .forecast_iter("Middle La Noscea", now, ForecastDirection::Forward)
+            .next()
+            .unwrap();
+        assert_eq!(first, current);
+        let snapshot = forecaster.weather_snapshot_for(&["Middle La Noscea"], now, 1);
+        assert_eq!(snapshot.zones[0].current, current);
+
+        let wind = forecaster
+            .find_next_weather_occurrence(
+                "Middle La Noscea",
+                now,
+                Weather::Wind,
+                SearchHorizon::Intervals(3),
+            )
+            .unwrap();
+        assert_eq!(wind.start_time, midnight + 40);
+        let run = forecaster
+            .find_next_weather_run(
+                "Middle La Noscea",
+                now,
+                &WeatherFilter::from(Weather::Wind),
+                1,
+                SearchHorizon::Intervals(3),
+            )
+            .unwrap();
+        assert_eq!(run.start_time, midnight + 40);
+        let transitions = forecaster.find_weather_transitions(
+            "Middle La Noscea",
+            now,
+            &WeatherFilter::from(Weather::Rain),
+            &WeatherFilter::from(Weather::Wind),
+            1,
+            SearchHorizon::Intervals(1),
+        );
+        assert_eq!(transitions.len(), 1);
+        assert_eq!(transitions[0].current.start_time, midnight + 40);
+    }
+
+    #[test]
+    fn test_forecasters_are_independent() {
+        let early = Forecaster::new(WeatherDatabase::builtin()).with_timing_offset(-30);
+        let late = early.with_timing_offset(30);
+        assert_eq!(
+            early.calculate_forecast("MLN", 1724738458, 0).start_time,
+            1724738370
+        );
+        assert_eq!(
+            late.calculate_forecast("MLN", 1724738458, 0).start_time,
+            1724738430
+        );
+        assert_eq!(early.timing_offset(), -30);
+    }
+
+    #[test]
+    fn test_forecaster_time_windows_use_offset() {
+        let forecaster = Forecaster::new(WeatherDatabase::builtin()).with_timing_offset(100);
+        let wind = WeatherFilter::from(Weather::Wind);
+        let range = EorzeanTimeRange::new((22, 0), (2, 0));
+        let windows = forecaster.find_weather_time_windows(
+            "MLN",
+            1724738458,
+            &wind,
+            &range,
+            1,
+            SearchHorizon::Intervals(1),
+        );
+        // With the offset, the Wind interval starting at 1724738500 is the one after the current interval
+        assert_eq!(
+            (windows[0].start_time, windows[0].end_time),
+            (1724738500, 1724738850)
+        );
+    }
+
+    #[test]
+    fn test_forecaster_precision() {
+        let forecaster = Forecaster::new(WeatherDatabase::builtin())
+            .with_timing_offset(25)
+            .with_precision(TimePrecision::Minute);
+        let weather = forecaster.calculate_forecast("Middle La Noscea", 1724738458, 0);
+        // 1724738425 and 1724739825 rounded down to the minute
+        assert_eq!(weather.start_time, 1724738400);
+        assert_eq!(weather.end_time, 1724739780);
+        assert_eq!(weather.start_time % 60, 0);
+    }
+
+    #[test]
+    fn test_forecaster_with_database() {
+        let custom = WeatherDatabase::from_file(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/data/custom_weather_data.json"
+        ))
+        .unwrap();
+        let forecaster = Forecaster::new(WeatherDatabase::builtin())
+            .with_timing_offset(5)
+            .with_database(&custom);
+        assert_eq!(forecaster.database(), &custom);
+        assert_eq!(forecaster.timing_offset(), 5);
+        assert_eq!(
+            forecaster.get_weather_by_time("Middle La Noscea", 1724738458),
+            Weather::Thunderstorms
+        );
+    }
+
+    #[test]
+    fn test_forecaster_neighbouring_weather() {
+        let forecaster =
+            Forecaster::new(WeatherDatabase::builtin()).with_neighbouring_weather(true);
+        let weather = forecaster.calculate_forecast("Middle La Noscea", 1724738458, 0);
+        assert_eq!(weather.weather, Weather::Wind);
+        assert_eq!(weather.previous_weather, Some(Weather::Rain));
+        assert_eq!(weather.next_weather, Some(Weather::FairSkies));
+
+        let forecasts: Vec<_> = forecaster
+            .forecast_iter("Middle La Noscea", 1724738458, ForecastDirection::Forward)
+            .take(4)
+            .collect();
+        for pair in forecasts.windows(2) {
+            assert_eq!(pair[0].next_weather, Some(pair[1].weather));
+            assert_eq!(pair[1].previous_weather, Some(pair[0].weather));
+        }
+
+        let snapshot = forecaster.weather_snapshot_for(&["Middle La Noscea"], 1724738458, 1);
+        let zone = snapshot.zone_by_name("Middle La Noscea").unwrap();
+        assert_eq!(zone.current.previous_weather, Some(zone.previous.weather));
+        assert_eq!(zone.current.next_weather, Some(zone.upcoming[0].weather));
+
+        // The intervals either side of the snapshot have their neighbours filled in too
+        let snapshot = forecaster.weather_snapshot(1724738458, 2);
+        for zone in &snapshot.zones {
+            assert_eq!(
+                zone.previous,
+                forecaster.calculate_forecast(zone.zone_id, 1724738458, -1)
+            );
+            assert_eq!(
+                zone.upcoming[1],
+                forecaster.calculate_forecast(zone.zone_id, 1724738458, 2)
+            );
+        }
+    }
+}
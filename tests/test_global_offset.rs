@@ -0,0 +1,36 @@
+extern crate ffxiv_chronowatcher;
+
+use ffxiv_chronowatcher::eorzean_weather::Weather;
+#[allow(deprecated)]
+use ffxiv_chronowatcher::eorzean_weather::{
+    find_next_weather_occurance, get_global_weather_timing_offset, set_global_weather_timing_offset,
+};
+
+use std::sync::Mutex;
+
+// These tests change the process wide timing offset, so they run in their own test binary and one at a time
+mod global_offset_tests {
+    use super::*;
+
+    static GLOBAL_OFFSET_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_set_global_offset() {
+        let _guard = GLOBAL_OFFSET_LOCK.lock().unwrap();
+        set_global_weather_timing_offset(1724738458);
+        assert_eq!(get_global_weather_timing_offset(), 1724738458);
+        set_global_weather_timing_offset(0);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_find_weather_by_time_with_offset() {
+        let _guard = GLOBAL_OFFSET_LOCK.lock().unwrap();
+        let timestamp = 1732396696;
+        set_global_weather_timing_offset(40);
+        let weather = find_next_weather_occurance("Eureka Pagos", timestamp, Weather::Fog);
+        assert_eq!(weather.start_time, 1732402040);
+        set_global_weather_timing_offset(0);
+    }
+}
@@ -0,0 +1,116 @@
+extern crate ffxiv_chronowatcher;
+
+use ffxiv_chronowatcher::eorzean_time::EorzeanTimeRange;
+use ffxiv_chronowatcher::eorzean_weather::{weather_window_set, Weather, WeatherFilter};
+use ffxiv_chronowatcher::forecaster::Forecaster;
+use ffxiv_chronowatcher::time_window_set::TimeWindowSet;
+use ffxiv_chronowatcher::weather_data::WeatherDatabase;
+
+use chrono::Duration;
+
+mod time_window_set_tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_are_sorted_and_joined() {
+        let set: TimeWindowSet = vec![(30, 40), (0, 10), (10, 20), (35, 50), (60, 60)]
+            .into_iter()
+            .collect();
+        assert_eq!(set.windows(), &[(0, 20), (30, 50)]);
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.bounds(), Some((0, 50)));
+        assert_eq!(set.total_duration(), Duration::seconds(40));
+
+        let mut set = set;
+        set.insert(20, 30);
+        assert_eq!(set.windows(), &[(0, 50)]);
+        set.insert(70, 60);
+        assert_eq!(set.windows(), &[(0, 50)]);
+        set.insert(-10, -5);
+        assert_eq!(
+            set.iter().copied().collect::<Vec<_>>(),
+            vec![(-10, -5), (0, 50)]
+        );
+        assert!(TimeWindowSet::from_window(5, 5).is_empty());
+        assert_eq!(TimeWindowSet::new().bounds(), None);
+    }
+
+    #[test]
+    fn test_lookup() {
+        let set: TimeWindowSet = vec![(0, 10), (20, 30)].into_iter().collect();
+        assert!(set.contains(0));
+        assert!(!set.contains(10));
+        assert_eq!(set.window_containing(25), Some((20, 30)));
+        assert_eq!(set.window_containing(15), None);
+        assert_eq!(set.next_window(15), Some((20, 30)));
+        assert_eq!(set.next_window(5), Some((0, 10)));
+        assert_eq!(set.next_window(30), None);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: TimeWindowSet = vec![(0, 10), (20, 30)].into_iter().collect();
+        let b: TimeWindowSet = vec![(5, 25)].into_iter().collect();
+        assert_eq!(a.union(&b).windows(), &[(0, 30)]);
+        assert_eq!(a.intersection(&b).windows(), &[(5, 10), (20, 25)]);
+        assert_eq!(a.difference(&b).windows(), &[(0, 5), (25, 30)]);
+        assert_eq!(b.difference(&a).windows(), &[(10, 20)]);
+        assert_eq!(
+            a.complement(-5, 35).windows(),
+            &[(-5, 0), (10, 20), (30, 35)]
+        );
+        assert_eq!(a.complement(5, 25).windows(), &[(10, 20)]);
+        assert_eq!(a.clip(5, 25).windows(), &[(5, 10), (20, 25)]);
+        assert!(a.intersection(&TimeWindowSet::new()).is_empty());
+        assert_eq!(a.difference(&TimeWindowSet::new()), a);
+    }
+
+    #[test]
+    fn test_merge_gaps() {
+        let set: TimeWindowSet = vec![(0, 10), (15, 20), (40, 50)].into_iter().collect();
+        assert_eq!(
+            set.merge_gaps(Duration::seconds(5)).windows(),
+            &[(0, 20), (40, 50)]
+        );
+        assert_eq!(set.merge_gaps(Duration::seconds(4)), set);
+        assert_eq!(set.merge_gaps(Duration::seconds(20)).windows(), &[(0, 50)]);
+    }
+
+    #[test]
+    fn test_weather_window_set() {
+        let rain_or_wind = WeatherFilter::any_of([Weather::Rain, Weather::Wind]);
+        let set = weather_window_set("Middle La Noscea", &rain_or_wind, 1724737500, 1724741200);
+        assert_eq!(set.windows(), &[(1724737500, 1724739800)]);
+
+        let wind = weather_window_set(
+            "Middle La Noscea",
+            &WeatherFilter::from(Weather::Wind),
+            1724737000,
+            1724741200,
+        );
+        let early_morning =
+            EorzeanTimeRange::new((2, 0), (4, 0)).earth_window_set(1724737000, 1724741200);
+        assert_eq!(
+            wind.intersection(&early_morning).windows(),
+            &[(1724738750, 1724739100)]
+        );
+
+        let offset = Forecaster::new(WeatherDatabase::builtin())
+            .with_timing_offset(40)
+            .weather_window_set(
+                "Middle La Noscea",
+                &WeatherFilter::from(Weather::Wind),
+                1724738000,
+                1724741200,
+            );
+        assert_eq!(offset.windows(), &[(1724738440, 1724739840)]);
+
+        let never = weather_window_set(
+            "Middle La Noscea",
+            &WeatherFilter::from(Weather::Snow),
+            1724737000,
+            1724741200,
+        );
+        assert!(never.is_empty());
+    }
+}
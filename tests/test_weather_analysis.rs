@@ -0,0 +1,162 @@
+extern crate ffxiv_chronowatcher;
+
+use chrono::Duration;
+use ffxiv_chronowatcher::eorzean_time::EorzeanTimeRange;
+use ffxiv_chronowatcher::eorzean_weather::{SearchHorizon, Weather, WeatherFilter};
+use ffxiv_chronowatcher::forecaster::Forecaster;
+use ffxiv_chronowatcher::weather_analysis::{
+    analyze_weather_wait, weather_probability, WeatherTarget,
+};
+use ffxiv_chronowatcher::weather_data::WeatherDatabase;
+
+mod weather_analysis_tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_weather_probability() {
+        let wet = WeatherTarget::Weather(WeatherFilter::any_of([Weather::Rain, Weather::Showers]));
+        assert_close(
+            weather_probability("Eastern La Noscea", &wet, 1724738400),
+            0.10,
+        );
+        let clearing = WeatherTarget::Transition {
+            previous: WeatherFilter::from(Weather::Fog),
+            current: WeatherFilter::from(Weather::ClearSkies),
+        };
+        assert_close(
+            weather_probability("Eastern La Noscea", &clearing, 1724738400),
+            0.05 * 0.45,
+        );
+    }
+
+    #[test]
+    fn test_time_window_probability() {
+        let rain = WeatherFilter::from(Weather::Rain);
+        let within_one_interval = WeatherTarget::TimeWindow {
+            filter: rain.clone(),
+            time_range: EorzeanTimeRange::new((8, 0), (12, 0)),
+        };
+        assert_close(
+            weather_probability("Eastern La Noscea", &within_one_interval, 1724738400),
+            0.05 / 3.0,
+        );
+        let across_intervals = WeatherTarget::TimeWindow {
+            filter: rain,
+            time_range: EorzeanTimeRange::new((6, 0), (10, 0)),
+        };
+        assert_close(
+            weather_probability("Eastern La Noscea", &across_intervals, 1724738400),
+            0.05 * 2.0 / 3.0,
+        );
+    }
+
+    #[test]
+    fn test_analyze_weather_wait() {
+        let rain = WeatherTarget::Weather(WeatherFilter::from(Weather::Rain));
+        let statistics = analyze_weather_wait(
+            "Middle La Noscea",
+            &rain,
+            1724738458,
+            SearchHorizon::Intervals(3000),
+        );
+        assert_eq!(statistics.intervals_scanned, 3001);
+        assert!((statistics.observed_probability - statistics.probability).abs() < 0.05);
+        assert!(statistics.occurrences > 0);
+        let median = statistics.median_wait.unwrap();
+        let p95 = statistics.p95_wait.unwrap();
+        assert!(median <= p95);
+        assert!(statistics.mean_wait.unwrap() <= p95);
+        assert!(p95 < statistics.longest_drought);
+        assert_eq!(statistics.longest_drought.num_seconds() % 1400, 0);
+        assert!(statistics.expected_wait().unwrap() > Duration::zero());
+    }
+
+    #[test]
+    fn test_analyze_single_interval() {
+        // In Middle La Noscea, the interval containing 1724738458 has Wind and the one before it has Rain
+        let wind = WeatherTarget::Weather(WeatherFilter::from(Weather::Wind));
+        let statistics = analyze_weather_wait(
+            "Middle La Noscea",
+            &wind,
+            1724738458,
+            SearchHorizon::Intervals(0),
+        );
+        assert_eq!(statistics.intervals_scanned, 1);
+        assert_eq!(statistics.observed_probability, 1.0);
+        assert_eq!(statistics.median_wait, Some(Duration::zero()));
+        assert_eq!(statistics.longest_drought, Duration::zero());
+        let after_rain = WeatherTarget::Transition {
+            previous: WeatherFilter::from(Weather::Rain),
+            current: WeatherFilter::from(Weather::Wind),
+        };
+        let statistics = analyze_weather_wait(
+            "Middle La Noscea",
+            &after_rain,
+            1724738458,
+            SearchHorizon::Intervals(0),
+        );
+        assert_eq!(statistics.occurrences, 1);
+    }
+
+    #[test]
+    fn test_analyze_impossible_weather() {
+        let snow = WeatherTarget::Weather(WeatherFilter::from(Weather::Snow));
+        let statistics = analyze_weather_wait(
+            "Limsa Lominsa",
+            &snow,
+            1724738458,
+            SearchHorizon::Duration(Duration::days(1)),
+        );
+        assert_eq!(statistics.probability, 0.0);
+        assert_eq!(statistics.expected_wait(), None);
+        assert_eq!(statistics.occurrences, 0);
+        assert_eq!(statistics.mean_wait, None);
+        assert_eq!(statistics.p95_wait, None);
+        assert_eq!(
+            statistics.longest_drought.num_seconds(),
+            1400 * statistics.intervals_scanned as i64
+        );
+    }
+
+    #[test]
+    fn test_versioned_weather_data() {
+        let database = WeatherDatabase::from_file(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/data/versioned_weather_data.json"
+        ))
+        .unwrap();
+        // Middle La Noscea is always Rain in 6.0 and always Clouds from 7.0, which goes live at 1719565200
+        let before_go_live = 1719565200 - 3000;
+        let rain = WeatherTarget::Weather(WeatherFilter::from(Weather::Rain));
+        assert_eq!(
+            database.weather_probability("Middle La Noscea", &rain, before_go_live),
+            1.0
+        );
+        assert_eq!(
+            database.weather_probability("Middle La Noscea", &rain, 1724738400),
+            0.0
+        );
+
+        // Lower La Noscea is only in 6.0, the scan skips the intervals after it is removed
+        let forecaster = Forecaster::new(&database);
+        let fog = WeatherTarget::Weather(WeatherFilter::from(Weather::Fog));
+        let statistics = forecaster.analyze_weather_wait(
+            "Lower La Noscea",
+            &fog,
+            before_go_live,
+            SearchHorizon::Intervals(10),
+        );
+        assert_eq!(statistics.probability, 1.0);
+        assert_eq!(statistics.intervals_scanned, 3);
+        assert_eq!(statistics.observed_probability, 1.0);
+    }
+}
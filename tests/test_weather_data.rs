@@ -0,0 +1,418 @@
+extern crate ffxiv_chronowatcher;
+
+use ffxiv_chronowatcher::eorzean_weather::{
+    calculate_forecast, get_weather_by_time, weather_chances, zones_with_weather,
+    ForecastDirection, SearchHorizon, Weather, WeatherChange, WeatherFilter,
+};
+use ffxiv_chronowatcher::forecaster::Forecaster;
+use ffxiv_chronowatcher::weather_data::{
+    DuplicateZoneName, WeatherDataError, WeatherDataVersion, WeatherDatabase, WeatherTable, ZoneId,
+};
+
+mod weather_data_tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_reports_duplicate_names() {
+        let duplicates = WeatherTable::builtin().duplicate_names();
+        assert_eq!(
+            duplicates,
+            vec![
+                DuplicateZoneName {
+                    name: "Mist".to_string(),
+                    ids: vec![ZoneId(136), ZoneId(339)],
+                },
+                DuplicateZoneName {
+                    name: "The Diadem".to_string(),
+                    ids: vec![ZoneId(512), ZoneId(624)],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_zones_are_addressable_by_id() {
+        let table = WeatherTable::builtin();
+        let diadems = table.zones_by_name("The Diadem");
+        assert_eq!(diadems.len(), 2);
+        assert_eq!(
+            table
+                .zone_by_id(ZoneId(512))
+                .unwrap()
+                .weather_for_target(35),
+            Some(Weather::FairSkies)
+        );
+        assert_eq!(
+            table
+                .zone_by_id(ZoneId(624))
+                .unwrap()
+                .weather_for_target(35),
+            Some(Weather::Fog)
+        );
+        assert_eq!(
+            table
+                .zone_by_id(ZoneId(512))
+                .unwrap()
+                .weather_for_target(99),
+            Some(Weather::Hyperelectricity)
+        );
+    }
+
+    #[test]
+    fn test_duplicate_name_resolves_to_last_definition() {
+        let table = WeatherTable::builtin();
+        assert_eq!(table.zone_by_name("Mist").unwrap().id, ZoneId(339));
+        let weather = calculate_forecast("The Diadem", 1724738458, 0);
+        assert_eq!(weather.zone_id, ZoneId(624));
+        assert_eq!(weather.zone_name, "The Diadem");
+    }
+
+    #[test]
+    fn test_duplicate_names_have_distinguishing_aliases() {
+        let table = WeatherTable::builtin();
+        assert_eq!(table.zone_by_name("Mist (136)").unwrap().id, ZoneId(136));
+        assert_eq!(table.zone_by_name("Mist (339)").unwrap().id, ZoneId(339));
+        assert_eq!(
+            table.zone_by_name("The Diadem (512)").unwrap().id,
+            ZoneId(512)
+        );
+        assert_eq!(
+            table.zone_by_name("The Diadem (624)").unwrap().id,
+            ZoneId(624)
+        );
+    }
+
+    #[test]
+    fn test_forecast_by_zone_id() {
+        let timestamp = 1724738458;
+        let weather = calculate_forecast(ZoneId(136), timestamp, 0);
+        assert_eq!(weather.zone_name, "Mist");
+        assert_eq!(weather.weather, get_weather_by_time(ZoneId(136), timestamp));
+    }
+
+    #[test]
+    fn test_forecast_by_string_reference() {
+        let timestamp = 1724738458;
+        let zone = String::from("Middle La Noscea");
+        let weather = calculate_forecast(&zone, timestamp, 0);
+        assert_eq!(weather.zone_name, "Middle La Noscea");
+        assert_eq!(weather.weather, get_weather_by_time(&zone, timestamp));
+        assert_eq!(weather.weather, Weather::Wind);
+    }
+
+    #[test]
+    fn test_builtin_rates_cover_every_target() {
+        for zone in WeatherTable::builtin().zones() {
+            assert!(
+                zone.weather_for_target(99).is_some(),
+                "{} does not cover 99",
+                zone.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_weather_chances_collapse_duplicate_rows() {
+        assert_eq!(
+            weather_chances("Gridania"),
+            vec![
+                (Weather::Rain, 20),
+                (Weather::Fog, 10),
+                (Weather::Clouds, 10),
+                (Weather::FairSkies, 30),
+                (Weather::ClearSkies, 30),
+            ]
+        );
+        assert_eq!(weather_chances("ELN")[4], (Weather::Rain, 5));
+        for zone in WeatherTable::builtin().zones() {
+            assert_eq!(
+                zone.weather_chances()
+                    .iter()
+                    .map(|(_, chance)| chance)
+                    .sum::<i32>(),
+                100,
+                "{}",
+                zone.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_weather_chance() {
+        let table = WeatherTable::builtin();
+        let eastern_la_noscea = table.zone_by_name("Eastern La Noscea").unwrap();
+        assert_eq!(eastern_la_noscea.weather_chance(Weather::Showers), 5);
+        assert_eq!(eastern_la_noscea.weather_chance(Weather::Snow), 0);
+    }
+
+    #[test]
+    fn test_zones_with_weather() {
+        let zones = zones_with_weather(Weather::Thunder);
+        assert!(!zones.is_empty());
+        assert!(zones.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        assert!(zones
+            .iter()
+            .all(|(zone, chance)| zone.weather_chance(Weather::Thunder) == *chance && *chance > 0));
+        let names: Vec<&str> = zones.iter().map(|(zone, _)| zone.name.as_str()).collect();
+        assert!(names.contains(&"Central Shroud"));
+        assert!(!names.contains(&"Limsa Lominsa"));
+    }
+
+    #[test]
+    fn test_duplicate_zone_id_is_rejected() {
+        let json = r#"[
+            {"id": 1, "name": "A", "rates": [["Fog", 100]]},
+            {"id": 1, "name": "B", "rates": [["Rain", 100]]}
+        ]"#;
+        assert!(WeatherTable::from_json_str(json).is_err());
+    }
+
+    #[test]
+    fn test_parse_weather_names() {
+        assert_eq!(
+            "Astromagnetic Storms".parse::<Weather>(),
+            Ok(Weather::AstroMagneticStorm)
+        );
+        assert_eq!(
+            "Hyperelectricity".parse::<Weather>(),
+            Ok(Weather::Hyperelectricity)
+        );
+        assert!("Sunshine".parse::<Weather>().is_err());
+    }
+
+    #[test]
+    fn test_builtin_json_round_trip() {
+        let json = WeatherTable::builtin().to_json_string();
+        // Weathers are written by their display name, the bundled file also uses an older spelling
+        let bundled = include_str!("../data/weather_data.json")
+            .replace("Astromagnetic Storms", "Astro-Magnetic Storms");
+        assert_eq!(json, bundled);
+        assert_eq!(
+            &WeatherTable::from_json_str(&json).unwrap(),
+            WeatherTable::builtin()
+        );
+    }
+
+    const CUSTOM_DATA_PATH: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/custom_weather_data.json"
+    );
+
+    #[test]
+    fn test_database_from_file() {
+        let database = WeatherDatabase::from_file(CUSTOM_DATA_PATH).unwrap();
+        assert_eq!(database.table().zones().len(), 2);
+        let weather = database
+            .forecaster()
+            .calculate_forecast("Middle La Noscea", 1724738458, 3);
+        assert_eq!(weather.weather, Weather::Thunderstorms);
+        assert_eq!(weather.zone_id, ZoneId(134));
+    }
+
+    #[test]
+    fn test_database_from_reader_and_str() {
+        let json = std::fs::read_to_string(CUSTOM_DATA_PATH).unwrap();
+        let from_reader = WeatherDatabase::from_reader(json.as_bytes()).unwrap();
+        let from_str = WeatherDatabase::from_json_str(&json).unwrap();
+        assert_eq!(from_reader, from_str);
+        assert!(from_str.table().zone_by_name("Limsa Lominsa").is_none());
+    }
+
+    #[test]
+    fn test_database_from_missing_file() {
+        let result = WeatherDatabase::from_file("does/not/exist.json");
+        assert!(matches!(result, Err(WeatherDataError::Io(_))));
+    }
+
+    #[test]
+    fn test_database_merged_with_builtin() {
+        let custom =
+            WeatherTable::from_json_str(&std::fs::read_to_string(CUSTOM_DATA_PATH).unwrap())
+                .unwrap();
+        let database = WeatherDatabase::builtin_merged_with(&custom);
+        let builtin_count = WeatherTable::builtin().zones().len();
+        assert_eq!(database.table().zones().len(), builtin_count + 1);
+        assert_eq!(
+            database.forecaster().get_weather_by_time("MLN", 1724738458),
+            Weather::Thunderstorms
+        );
+        assert_eq!(
+            database.table().zone_by_id(ZoneId(134)).unwrap().name,
+            "Middle La Noscea"
+        );
+        assert!(database.table().zone_by_name("Limsa Lominsa").is_some());
+        let fog = database
+            .forecaster()
+            .find_next_weather_occurrence(
+                "Test Zone",
+                1724738458,
+                Weather::Fog,
+                SearchHorizon::Intervals(100),
+            )
+            .unwrap();
+        assert_eq!(fog.zone_id, ZoneId(9000));
+    }
+
+    const VERSIONED_DATA_PATH: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/versioned_weather_data.json"
+    );
+
+    #[test]
+    fn test_versioned_database_orders_versions() {
+        let database = WeatherDatabase::from_file(VERSIONED_DATA_PATH).unwrap();
+        let patches: Vec<Option<&str>> = database
+            .versions()
+            .iter()
+            .map(|v| v.patch.as_deref())
+            .collect();
+        assert_eq!(patches, vec![Some("6.0"), Some("7.0")]);
+        assert_eq!(
+            database.version("7.0").unwrap().effective_from,
+            Some(1719565200)
+        );
+    }
+
+    #[test]
+    fn test_versioned_database_picks_table_by_time() {
+        let database = WeatherDatabase::from_file(VERSIONED_DATA_PATH).unwrap();
+        // Before the first go-live date the earliest version is used
+        assert_eq!(
+            database
+                .forecaster()
+                .get_weather_by_time("Middle La Noscea", 1600000000),
+            Weather::Rain
+        );
+        assert_eq!(
+            database
+                .forecaster()
+                .get_weather_by_time("Middle La Noscea", 1700000000),
+            Weather::Rain
+        );
+        assert_eq!(
+            database
+                .forecaster()
+                .get_weather_by_time("Middle La Noscea", 1724738458),
+            Weather::Clouds
+        );
+        assert_eq!(
+            database.version_at(1719565200).patch.as_deref(),
+            Some("7.0")
+        );
+        assert_eq!(
+            database.version_at(1719565199).patch.as_deref(),
+            Some("6.0")
+        );
+    }
+
+    #[test]
+    fn test_versioned_forecast_crosses_go_live() {
+        let database = WeatherDatabase::from_file(VERSIONED_DATA_PATH).unwrap();
+        let before_go_live = 1719565200 - 3000;
+        assert_eq!(
+            database
+                .forecaster()
+                .calculate_forecast("Middle La Noscea", before_go_live, 0)
+                .weather,
+            Weather::Rain
+        );
+        assert_eq!(
+            database
+                .forecaster()
+                .calculate_forecast("Middle La Noscea", before_go_live, 4)
+                .weather,
+            Weather::Clouds
+        );
+        let clouds = database
+            .forecaster()
+            .find_next_weather_occurrence(
+                ZoneId(134),
+                before_go_live,
+                Weather::Clouds,
+                SearchHorizon::Intervals(100),
+            )
+            .unwrap();
+        assert!(clouds.start_time >= 1719565200 - 1400);
+    }
+
+    #[test]
+    fn test_versioned_snapshot_leaves_out_removed_zones() {
+        let database = WeatherDatabase::from_file(VERSIONED_DATA_PATH).unwrap();
+        let before_go_live = 1719565200 - 3000;
+        let snapshot = database.forecaster().weather_snapshot(before_go_live, 0);
+        assert_eq!(snapshot.zones.len(), 2);
+        let snapshot = database.forecaster().weather_snapshot(before_go_live, 4);
+        assert_eq!(snapshot.zones.len(), 1);
+        let middle_la_noscea = &snapshot.zones[0];
+        assert_eq!(middle_la_noscea.current.weather, Weather::Rain);
+        assert_eq!(middle_la_noscea.upcoming[3].weather, Weather::Clouds);
+    }
+
+    #[test]
+    fn test_versioned_scans_stop_at_removed_zone() {
+        let database = WeatherDatabase::from_file(VERSIONED_DATA_PATH).unwrap();
+        let forecaster = Forecaster::new(&database);
+        let before_go_live = 1719565200 - 3000;
+        // Lower La Noscea is only in 6.0, whose last interval starts at 1719564000
+        let starts: Vec<i64> = forecaster
+            .forecast_iter(
+                "Lower La Noscea",
+                before_go_live,
+                ForecastDirection::Forward,
+            )
+            .map(|forecast| forecast.start_time)
+            .collect();
+        assert_eq!(starts, vec![1719561200, 1719562600, 1719564000]);
+
+        let changes: Vec<WeatherChange> = forecaster
+            .weather_changes(
+                &["Lower La Noscea", "Middle La Noscea"],
+                before_go_live,
+                before_go_live + 14000,
+            )
+            .collect();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].zone_id, ZoneId(134));
+        assert_eq!(changes[0].time, 1719565400);
+
+        let run = forecaster
+            .find_next_weather_run(
+                "Lower La Noscea",
+                before_go_live,
+                &WeatherFilter::from(Weather::Fog),
+                1,
+                SearchHorizon::Intervals(10),
+            )
+            .unwrap();
+        assert_eq!(run.end_time, 1719565400);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_versioned_zone_removed_in_later_patch() {
+        let database = WeatherDatabase::from_file(VERSIONED_DATA_PATH).unwrap();
+        database
+            .forecaster()
+            .get_weather_by_time("Lower La Noscea", 1724738458);
+    }
+
+    #[test]
+    fn test_conflicting_versions_are_rejected() {
+        let version = WeatherDataVersion {
+            patch: Some("7.0".to_string()),
+            effective_from: Some(1719565200),
+            table: WeatherTable::default(),
+        };
+        let result = WeatherDatabase::from_versions(vec![version.clone(), version.clone()]);
+        assert!(matches!(
+            result,
+            Err(WeatherDataError::ConflictingVersions { .. })
+        ));
+        let mut database = WeatherDatabase::from_versions(vec![version.clone()]).unwrap();
+        assert!(database.add_version(version).is_err());
+        assert!(matches!(
+            WeatherDatabase::from_versions(vec![]),
+            Err(WeatherDataError::NoVersions)
+        ));
+    }
+}
@@ -0,0 +1,194 @@
+extern crate ffxiv_chronowatcher;
+
+use ffxiv_chronowatcher::eorzean_time::EorzeanTimeRange;
+use ffxiv_chronowatcher::eorzean_weather::{Weather, WeatherFilter};
+use ffxiv_chronowatcher::forecaster::Forecaster;
+use ffxiv_chronowatcher::weather_data::WeatherDatabase;
+use ffxiv_chronowatcher::weather_query::{
+    query_weather_windows, QueryCondition, QueryError, WeatherQuery,
+};
+
+mod weather_query_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        let query: WeatherQuery = r#"zone:"Middle La Noscea" weather:Rain|Showers after:fog et:06:00-10:00 moon:"New Moon""#
+            .parse()
+            .unwrap();
+        assert_eq!(
+            query.conditions,
+            vec![
+                QueryCondition::Zone("Middle La Noscea".to_string()),
+                QueryCondition::Weather(WeatherFilter::any_of([Weather::Rain, Weather::Showers])),
+                QueryCondition::After(WeatherFilter::from(Weather::Fog)),
+                QueryCondition::EorzeanTime(vec![EorzeanTimeRange::new((6, 0), (10, 0))]),
+                QueryCondition::MoonPhase(vec![0]),
+            ]
+        );
+        assert_eq!(query.zone(), Some("Middle La Noscea"));
+
+        let query = WeatherQuery::parse(
+            "  ET:22-2|12:30-13:00   Weather:clearskies|\"fair skies\" zone:MLN ",
+        )
+        .unwrap();
+        assert_eq!(
+            query.conditions[0],
+            QueryCondition::EorzeanTime(vec![
+                EorzeanTimeRange::new((22, 0), (2, 0)),
+                EorzeanTimeRange::new((12, 30), (13, 0)),
+            ])
+        );
+        assert_eq!(
+            query.conditions[1],
+            QueryCondition::Weather(WeatherFilter::any_of([
+                Weather::ClearSkies,
+                Weather::FairSkies
+            ]))
+        );
+        assert!(WeatherQuery::parse("moon:\"Full Moon\"")
+            .unwrap()
+            .zone()
+            .is_none());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |query: &str| WeatherQuery::parse(query).unwrap_err();
+        assert_eq!(error("   "), QueryError::EmptyQuery);
+        assert_eq!(
+            error("zone:MLN Rain"),
+            QueryError::ExpectedKey {
+                column: 10,
+                found: "Rain".to_string()
+            }
+        );
+        assert_eq!(
+            error("zone:MLN wether:Rain"),
+            QueryError::UnknownKey {
+                column: 10,
+                key: "wether".to_string()
+            }
+        );
+        assert_eq!(
+            error("zone:MLN weather:Rain|"),
+            QueryError::MissingValue {
+                column: 23,
+                key: "weather".to_string()
+            }
+        );
+        assert_eq!(
+            error("zone:\"Middle La Noscea"),
+            QueryError::UnterminatedQuote { column: 6 }
+        );
+        assert_eq!(
+            error("zone:\"Middle La Noscea\"weather:Rain"),
+            QueryError::UnexpectedCharacter {
+                column: 24,
+                character: 'w'
+            }
+        );
+        assert_eq!(
+            error("zone:MLN weather:Rain|Sunshine"),
+            QueryError::InvalidValue {
+                column: 23,
+                key: "weather".to_string(),
+                value: "Sunshine".to_string(),
+                expected: "a weather such as Rain or \"Clear Skies\"",
+            }
+        );
+        assert!(matches!(
+            error("et:25:00-02:00"),
+            QueryError::InvalidValue { column: 4, .. }
+        ));
+        assert!(matches!(error("et:06:00"), QueryError::InvalidValue { .. }));
+        assert!(matches!(
+            error("moon:Blue"),
+            QueryError::InvalidValue { .. }
+        ));
+        assert_eq!(
+            error("zone:MLN zone:CCH"),
+            QueryError::MultipleZones { column: 10 }
+        );
+        assert_eq!(
+            error("zone:MLN|CCH"),
+            QueryError::MultipleZones { column: 10 }
+        );
+        assert_eq!(
+            error("weather:Rain et:06:00-10:00"),
+            QueryError::MissingZone
+        );
+    }
+
+    #[test]
+    fn test_error_messages() {
+        assert_eq!(
+            WeatherQuery::parse("zone:MLN wether:Rain")
+                .unwrap_err()
+                .to_string(),
+            "Unknown key 'wether' at column 10, expected one of zone, weather, after, et or moon"
+        );
+        assert_eq!(
+            WeatherQuery::parse("zone:MLN weather:Sunshine").unwrap_err().to_string(),
+            "Invalid value 'Sunshine' for 'weather' at column 18, expected a weather such as Rain or \"Clear Skies\""
+        );
+        let error = query_weather_windows("zone:Atlantis weather:Rain", 1724737000, 1724741200)
+            .unwrap_err();
+        assert!(matches!(error, QueryError::UnknownZone { ref zone, .. } if zone == "Atlantis"));
+        assert!(error.to_string().starts_with("Unknown zone 'Atlantis'"));
+    }
+
+    #[test]
+    fn test_query_windows() {
+        let windows =
+            query_weather_windows("zone:MLN weather:Wind after:Rain", 1724737000, 1724741200)
+                .unwrap();
+        assert_eq!(windows.windows(), &[(1724738400, 1724739800)]);
+
+        let windows =
+            query_weather_windows("zone:MLN weather:Rain|Wind", 1724737500, 1724741200).unwrap();
+        assert_eq!(windows.windows(), &[(1724737500, 1724739800)]);
+
+        let windows = query_weather_windows(
+            "zone:MLN weather:Wind after:Rain et:02:00-04:00",
+            1724737000,
+            1724741200,
+        )
+        .unwrap();
+        assert_eq!(windows.windows(), &[(1724738750, 1724739100)]);
+
+        let windows =
+            query_weather_windows("zone:MLN weather:Wind after:Clouds", 1724737000, 1724741200)
+                .unwrap();
+        assert!(windows.is_empty());
+    }
+
+    #[test]
+    fn test_query_time_conditions() {
+        let windows =
+            query_weather_windows("moon:\"Waning Crescent\"", 1724730000, 1724760000).unwrap();
+        assert_eq!(windows.windows(), &[(1724738400, 1724755200)]);
+        let windows =
+            query_weather_windows("moon:waningcrescent et:00:00-08:00", 1724730000, 1724745000)
+                .unwrap();
+        assert_eq!(
+            windows.windows(),
+            &[(1724738400, 1724739800), (1724742600, 1724744000)]
+        );
+        assert!(
+            query_weather_windows("moon:\"New Moon\"", 1724738400, 1724755200)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_query_with_forecaster() {
+        let query = WeatherQuery::parse("zone:MLN weather:Wind after:Rain").unwrap();
+        let windows = Forecaster::new(WeatherDatabase::builtin())
+            .with_timing_offset(40)
+            .query_windows(&query, 1724737000, 1724741200)
+            .unwrap();
+        assert_eq!(windows.windows(), &[(1724738440, 1724739840)]);
+    }
+}
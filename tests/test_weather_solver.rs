@@ -0,0 +1,85 @@
+extern crate ffxiv_chronowatcher;
+
+use ffxiv_chronowatcher::eorzean_time::EorzeanTimeRange;
+use ffxiv_chronowatcher::eorzean_weather::{calculate_forecast, Weather};
+use ffxiv_chronowatcher::weather_solver::{solve_weather_observations, WeatherObservation};
+
+mod weather_solver_tests {
+    use super::*;
+
+    const TIMESTAMP: i64 = 1724738458;
+    const WEEK: i64 = 7 * 24 * 60 * 60;
+
+    fn observe(zone: &str) -> WeatherObservation<&str> {
+        WeatherObservation::new(zone, calculate_forecast(zone, TIMESTAMP, 0).weather)
+    }
+
+    #[test]
+    fn test_solve_single_observation() {
+        let observations = [WeatherObservation::new("Middle La Noscea", Weather::Wind)];
+        let matches = solve_weather_observations(&observations, TIMESTAMP - WEEK, TIMESTAMP + WEEK);
+        assert!(matches.iter().any(|m| m.start_time == 1724738400));
+        for m in &matches {
+            assert_eq!(
+                calculate_forecast("Middle La Noscea", m.start_time, 0).weather,
+                Weather::Wind
+            );
+            assert_eq!(m.windows, vec![(m.start_time, m.end_time)]);
+        }
+    }
+
+    #[test]
+    fn test_solve_several_zones_narrows_matches() {
+        let zones = [
+            "Middle La Noscea",
+            "Eureka Pagos",
+            "Mor Dhona",
+            "Gridania",
+            "Western Thanalan",
+            "The Fringes",
+        ];
+        let single =
+            solve_weather_observations(&[observe(zones[0])], TIMESTAMP - WEEK, TIMESTAMP + WEEK);
+        let observations: Vec<WeatherObservation<&str>> =
+            zones.iter().map(|zone| observe(zone)).collect();
+        let matches = solve_weather_observations(&observations, TIMESTAMP - WEEK, TIMESTAMP + WEEK);
+        assert!(matches.len() < single.len());
+        assert!(matches.iter().any(|m| m.start_time == 1724738400));
+    }
+
+    #[test]
+    fn test_solve_with_previous_weather_and_eorzean_time() {
+        // Middle La Noscea had Rain and then Wind at 1724738400, which is midnight ET
+        let observations = [WeatherObservation::new("Middle La Noscea", Weather::Wind)
+            .after(Weather::Rain)
+            .at_eorzean_time(EorzeanTimeRange::new((22, 0), (2, 0)))];
+        let matches = solve_weather_observations(&observations, TIMESTAMP - WEEK, TIMESTAMP + WEEK);
+        let found = matches.iter().find(|m| m.start_time == 1724738400).unwrap();
+        assert_eq!(found.windows, vec![(1724738400, 1724738750)]);
+        for m in &matches {
+            assert_eq!(
+                calculate_forecast("Middle La Noscea", m.start_time, -1).weather,
+                Weather::Rain
+            );
+            // The interval from 08:00 to 16:00 ET does not overlap 22:00 to 02:00
+            assert_ne!(m.start_time % 4200, 1400);
+            assert!(m.windows.iter().all(|(start, end)| end - start <= 350));
+        }
+        let wrong_previous =
+            [WeatherObservation::new("Middle La Noscea", Weather::Wind).after(Weather::Wind)];
+        let matches = solve_weather_observations(&wrong_previous, 1724738400, 1724739800);
+        assert!(matches.is_empty());
+    }
+
+    #[test]
+    fn test_solve_impossible_observation() {
+        let observations = [WeatherObservation::new("Limsa Lominsa", Weather::Snow)];
+        assert!(
+            solve_weather_observations(&observations, TIMESTAMP - WEEK, TIMESTAMP + WEEK)
+                .is_empty()
+        );
+        assert!(
+            solve_weather_observations(&[observe("Gridania")], TIMESTAMP, TIMESTAMP).is_empty()
+        );
+    }
+}
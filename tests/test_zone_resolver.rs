@@ -0,0 +1,98 @@
+extern crate ffxiv_chronowatcher;
+
+use ffxiv_chronowatcher::eorzean_weather::calculate_forecast;
+use ffxiv_chronowatcher::weather_data::WeatherTable;
+use ffxiv_chronowatcher::zone_resolver::{normalize_zone_name, MatchKind, ZoneResolution};
+
+mod zone_resolver_tests {
+    use super::*;
+
+    fn resolved_name(query: &str) -> Option<String> {
+        WeatherTable::builtin()
+            .resolve_zone(query)
+            .zone()
+            .map(|zone| zone.name.clone())
+    }
+
+    #[test]
+    fn test_normalize_zone_name() {
+        assert_eq!(
+            normalize_zone_name("Ul'dah - Steps of Thal"),
+            "uldah steps of thal"
+        );
+        assert_eq!(normalize_zone_name("  Kozama'uka "), "kozamauka");
+        assert_eq!(normalize_zone_name("Éorzéa"), "eorzea");
+    }
+
+    #[test]
+    fn test_resolve_case_and_punctuation() {
+        assert_eq!(resolved_name("mor dhona").as_deref(), Some("Mor Dhona"));
+        assert_eq!(resolved_name("uldah").as_deref(), Some("Ul'dah"));
+        assert_eq!(resolved_name("radz at han").as_deref(), Some("Radz-at-Han"));
+        assert_eq!(resolved_name("fringes").as_deref(), Some("The Fringes"));
+    }
+
+    #[test]
+    fn test_resolve_partial_words() {
+        assert_eq!(
+            resolved_name("Rak'tika").as_deref(),
+            Some("The Rak'tika Greatwood")
+        );
+        assert_eq!(
+            resolved_name("Old Sharlayan city").as_deref(),
+            Some("Old Sharlayan")
+        );
+    }
+
+    #[test]
+    fn test_resolve_alias() {
+        assert_eq!(resolved_name("MLN").as_deref(), Some("Middle La Noscea"));
+        assert_eq!(
+            resolved_name("cch").as_deref(),
+            Some("Coerthas Central Highlands")
+        );
+    }
+
+    #[test]
+    fn test_resolve_misspelling() {
+        assert_eq!(resolved_name("Lakelnd").as_deref(), Some("Lakeland"));
+        assert_eq!(resolved_name("Kholusa").as_deref(), Some("Kholusia"));
+    }
+
+    #[test]
+    fn test_resolve_ambiguous() {
+        match WeatherTable::builtin().resolve_zone("shroud") {
+            ZoneResolution::Ambiguous(matches) => {
+                let names: Vec<&str> = matches.iter().map(|m| m.zone.name.as_str()).collect();
+                assert_eq!(names.len(), 4);
+                assert!(names.contains(&"Central Shroud"));
+                assert!(names.contains(&"North Shroud"));
+                assert!(matches.iter().all(|m| m.kind == MatchKind::Words));
+            }
+            other => panic!("Expected an ambiguous resolution, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_resolve_not_found() {
+        assert_eq!(
+            WeatherTable::builtin().resolve_zone("Somewhere Not Here"),
+            ZoneResolution::NotFound
+        );
+    }
+
+    #[test]
+    fn test_suggestions_are_ranked() {
+        let suggestions = WeatherTable::builtin().suggest_zones("ul'dah", 3);
+        assert_eq!(suggestions[0].zone.name, "Ul'dah");
+        assert_eq!(suggestions[0].kind, MatchKind::Normalized);
+        assert_eq!(suggestions[1].zone.name, "Ul'dah - Steps of Thal");
+    }
+
+    #[test]
+    fn test_forecast_with_inexact_zone_name() {
+        let timestamp = 1724738458;
+        let weather = calculate_forecast("middle la noscea", timestamp, 0);
+        assert_eq!(weather.zone_name, "Middle La Noscea");
+    }
+}